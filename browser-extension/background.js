@@ -0,0 +1,40 @@
+// 每 30 秒和切换标签页时，把前台标签页的域名发给 NekoTick；
+// 浏览器不在前台或电脑空闲时发送 null
+const BEAT_MINUTES = 0.5;
+
+async function activeDomain() {
+  const { state } = await chrome.storage.session.get('state');
+  if (state && state !== 'active') return null;
+  const window = await chrome.windows.getLastFocused();
+  if (!window.focused) return null;
+  const [tab] = await chrome.tabs.query({ active: true, windowId: window.id });
+  if (!tab?.url) return null;
+  const url = new URL(tab.url);
+  return url.protocol === 'http:' || url.protocol === 'https:' ? url.host : null;
+}
+
+async function beat() {
+  const { endpoint } = await chrome.storage.local.get('endpoint');
+  if (!endpoint) return;
+  try {
+    await fetch(`http://127.0.0.1:${endpoint.port}/heartbeat`, {
+      method: 'POST',
+      headers: { Authorization: `Bearer ${endpoint.token}`, 'Content-Type': 'application/json' },
+      body: JSON.stringify({ domain: await activeDomain() }),
+    });
+  } catch {
+    // NekoTick 没有运行或关闭了网站记录
+  }
+}
+
+chrome.alarms.create('beat', { periodInMinutes: BEAT_MINUTES });
+chrome.alarms.onAlarm.addListener(beat);
+chrome.tabs.onActivated.addListener(beat);
+chrome.tabs.onUpdated.addListener((_id, change, tab) => {
+  if (tab.active && change.url) beat();
+});
+chrome.windows.onFocusChanged.addListener(beat);
+chrome.idle.onStateChanged.addListener(async (state) => {
+  await chrome.storage.session.set({ state });
+  beat();
+});
@@ -0,0 +1,17 @@
+const field = document.getElementById('endpoint');
+const status = document.getElementById('status');
+
+chrome.storage.local.get('endpoint').then(({ endpoint }) => {
+  if (endpoint) field.value = JSON.stringify(endpoint);
+});
+
+document.getElementById('save').addEventListener('click', async () => {
+  try {
+    const { port, token } = JSON.parse(field.value);
+    if (!Number.isInteger(port) || typeof token !== 'string') throw new Error();
+    await chrome.storage.local.set({ endpoint: { port, token } });
+    status.textContent = '已保存';
+  } catch {
+    status.textContent = '格式不对，请重新从 NekoTick 复制';
+  }
+});
@@ -1,3 +1,41 @@
+// Each app command gets generated `allow-<command>` / `deny-<command>`
+// permissions, so a window can only call what its capability lists. Keep this
+// in sync with `generate_handler!` in `src/lib.rs`.
+const COMMANDS: &[&str] = &[
+    "show_drag_overlay",
+    "show_selection_drag_overlay",
+    "hide_drag_overlay",
+    "get_data_paths",
+    "load_groups",
+    "save_group",
+    "adopt_group",
+    "delete_group",
+    "move_tasks",
+    "check_vault",
+    "repair_vault",
+    "migrate_legacy",
+    "time_audit",
+    "timer_status",
+    "timer_start",
+    "timer_stop",
+    "pomodoro_start",
+    "pomodoro_stop",
+    "timer_resolve_idle",
+    "web_endpoint",
+    "get_settings",
+    "save_settings",
+    "load_progress",
+    "save_progress",
+    "load_time_log",
+    "export_drag",
+    "import_files",
+    "log_frontend",
+    "collect_diagnostics",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new().app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
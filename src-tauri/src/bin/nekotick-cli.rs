@@ -0,0 +1,483 @@
+// Headless access to the vault: reads and writes the same `tasks/*.md` and
+// `progress/progress.md` files as the app, so a running window picks the
+// changes up through its file watcher.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use nekotick_lib::vault::{
+    self, audit, check, migrate, ops, progress, store, DataPaths, Group, Priority, ProgressEntry, Task,
+};
+use serde_json::json;
+
+const USAGE: &str = "\
+Usage: nekotick-cli [--json] [--data-dir <path>] <command> [args]
+
+Commands:
+  groups                              List groups
+  ls [group] [--all]                  List tasks (open only unless --all)
+  add <text...> [-g <group>] [--parent <id>] [--priority <p>]
+                                      Add a task (default group: default)
+  done <id> [--undo]                  Complete (or reopen) a task
+  mv <id...> <group>                  Move tasks and their subtasks
+  check [--repair]                    Report duplicate ids, missing parents,
+                                      parent cycles and bad orders; fix them
+  audit                               Estimated vs actual minutes per group
+                                      and per #tag
+  migrate [file]                      Import a legacy nekotick.md as a new
+                                      group (default: Documents/NekoTick)
+  progress ls                         List progress items and counters
+  progress bump <id|title> [--by <n>] Press + on an item n times (default 1)
+
+Options before the command apply to every command; after it, `--` ends
+the options, so `add -- -v flag` adds the text \"-v flag\".
+Task ids may be shortened to any unique prefix.
+Groups can be given by id or by name.";
+
+struct Cli {
+    json: bool,
+    help: bool,
+    data_dir: Option<PathBuf>,
+    args: Vec<String>,
+}
+
+impl Cli {
+    // Take the options before the command; the rest is left for the command
+    fn parse(mut args: Vec<String>) -> Result<Self, String> {
+        let mut cli = Cli {
+            json: false,
+            help: false,
+            data_dir: None,
+            args: Vec::new(),
+        };
+        let data_dir = vault::paths::ARG_DATA_DIR;
+        while let Some(arg) = args.first().filter(|a| a.starts_with('-')) {
+            match arg.as_str() {
+                "--" => {
+                    args.remove(0);
+                    break;
+                }
+                "--json" => cli.json = true,
+                "--help" | "-h" => cli.help = true,
+                _ if arg == data_dir => {
+                    if args.len() < 2 {
+                        return Err(format!("{} needs a value", data_dir));
+                    }
+                    cli.data_dir = Some(PathBuf::from(args.remove(1)));
+                }
+                _ => match arg.strip_prefix(data_dir).and_then(|v| v.strip_prefix('=')) {
+                    Some(value) => cli.data_dir = Some(PathBuf::from(value)),
+                    None => return Err(format!("unknown option {:?}\n\n{}", arg, USAGE)),
+                },
+            }
+            args.remove(0);
+        }
+        cli.args = args;
+        Ok(cli)
+    }
+
+    // Command options come before a `--`; everything after it is text
+    fn options_end(&self) -> usize {
+        self.args.iter().position(|a| a == "--").unwrap_or(self.args.len())
+    }
+
+    // Remove `--flag` and report whether it was present
+    fn flag(&mut self, names: &[&str]) -> bool {
+        match self.args[..self.options_end()]
+            .iter()
+            .position(|a| names.contains(&a.as_str()))
+        {
+            Some(i) => {
+                self.args.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Remove `--opt value` / `--opt=value` and return the value
+    fn option(&mut self, names: &[&str]) -> Result<Option<String>, String> {
+        for i in 0..self.options_end() {
+            let arg = &self.args[i];
+            if names.contains(&arg.as_str()) {
+                if i + 1 >= self.options_end() {
+                    return Err(format!("{} needs a value", arg));
+                }
+                let value = self.args.remove(i + 1);
+                self.args.remove(i);
+                return Ok(Some(value));
+            }
+            for name in names {
+                if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+                    let value = value.to_string();
+                    self.args.remove(i);
+                    return Ok(Some(value));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // The command's arguments once its options are taken: drops the `--`
+    fn positional(&mut self) -> &[String] {
+        if let Some(end) = self.args.iter().position(|a| a == "--") {
+            self.args.remove(end);
+        }
+        &self.args
+    }
+
+    fn print(&self, value: serde_json::Value, text: impl FnOnce() -> String) {
+        if self.json {
+            println!("{}", value);
+        } else {
+            println!("{}", text());
+        }
+    }
+}
+
+fn load_groups(paths: &DataPaths) -> Result<Vec<Group>, String> {
+    store::load_groups(paths).map_err(|e| format!("cannot read {}: {}", paths.tasks.display(), e))
+}
+
+fn save_group(paths: &DataPaths, group: &Group) -> Result<(), String> {
+    store::save_group(paths, group).map_err(|e| format!("cannot save group {:?}: {}", group.id, e))
+}
+
+fn group_index(groups: &[Group], query: &str) -> Result<usize, String> {
+    let group = ops::find_group(groups, query).ok_or_else(|| format!("no group {:?}", query))?;
+    Ok(groups.iter().position(|g| g.id == group.id).unwrap_or_default())
+}
+
+fn print_tree(group: &Group, all: bool) {
+    fn walk(group: &Group, parent: Option<&str>, depth: usize, all: bool) {
+        let mut children: Vec<&Task> = group
+            .tasks
+            .iter()
+            .filter(|t| t.parent_id.as_deref() == parent)
+            .collect();
+        children.sort_by_key(|t| t.order);
+        for task in children {
+            if task.completed && !all {
+                continue;
+            }
+            let mark = if task.completed { "x" } else { " " };
+            let priority = match task.priority {
+                Some(p) if p != Priority::Default => format!(" !{}", p.as_str()),
+                _ => String::new(),
+            };
+            println!(
+                "{}[{}] {}{}  {}",
+                "  ".repeat(depth),
+                mark,
+                task.content,
+                priority,
+                task.id
+            );
+            walk(group, Some(&task.id), depth + 1, all);
+        }
+    }
+    walk(group, None, 0, all);
+}
+
+fn cmd_groups(cli: &Cli, paths: &DataPaths) -> Result<(), String> {
+    let groups = load_groups(paths)?;
+    let rows: Vec<_> = groups
+        .iter()
+        .map(|g| {
+            let done = g.tasks.iter().filter(|t| t.completed).count();
+            (g, done)
+        })
+        .collect();
+    cli.print(
+        json!(rows
+            .iter()
+            .map(|(g, done)| json!({
+                "id": g.id,
+                "name": g.name,
+                "pinned": g.pinned,
+                "tasks": g.tasks.len(),
+                "done": done,
+            }))
+            .collect::<Vec<_>>()),
+        || {
+            rows.iter()
+                .map(|(g, done)| {
+                    let pin = if g.pinned { "*" } else { " " };
+                    format!("{} {}  {}  ({}/{})", pin, g.id, g.name, done, g.tasks.len())
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+    );
+    Ok(())
+}
+
+fn cmd_ls(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let all = cli.flag(&["--all", "-a"]);
+    let query = cli
+        .positional()
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "default".to_string());
+    let groups = load_groups(paths)?;
+    let group = &groups[group_index(&groups, &query)?];
+
+    if cli.json {
+        let tasks: Vec<&Task> = group.tasks.iter().filter(|t| all || !t.completed).collect();
+        println!("{}", json!({ "id": group.id, "name": group.name, "tasks": tasks }));
+    } else {
+        println!("# {}", group.name);
+        print_tree(group, all);
+    }
+    Ok(())
+}
+
+fn cmd_add(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let group_query = cli.option(&["--group", "-g"])?.unwrap_or_else(|| "default".to_string());
+    let parent = cli.option(&["--parent"])?;
+    let priority = match cli.option(&["--priority", "-p"])? {
+        Some(p) => Some(Priority::parse(&p).ok_or_else(|| format!("unknown priority {:?}", p))?),
+        None => None,
+    };
+    let content = cli.positional().join(" ");
+
+    let mut groups = load_groups(paths)?;
+    let gi = match group_index(&groups, &group_query) {
+        Ok(gi) => gi,
+        // The app creates the inbox on first start; do the same here
+        Err(_) if group_query == "default" => {
+            groups.push(Group::new("default", "收集箱"));
+            groups.len() - 1
+        }
+        Err(e) => return Err(e),
+    };
+
+    let parent = match parent {
+        Some(query) => {
+            let (pg, pt) = ops::find_task(&groups, &query)?;
+            if pg != gi {
+                return Err(format!("parent {:?} is in another group", query));
+            }
+            Some(groups[pg].tasks[pt].id.clone())
+        }
+        None => None,
+    };
+
+    let group = &mut groups[gi];
+    let id = ops::add_task(group, &content, parent.as_deref(), priority)?;
+    save_group(paths, group)?;
+
+    let task = group.tasks.iter().find(|t| t.id == id);
+    cli.print(json!(task), || id.clone());
+    Ok(())
+}
+
+fn cmd_done(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let undo = cli.flag(&["--undo"]);
+    let query = cli.positional().first().ok_or("missing task id")?.clone();
+
+    let mut groups = load_groups(paths)?;
+    let (gi, ti) = ops::find_task(&groups, &query)?;
+    let id = groups[gi].tasks[ti].id.clone();
+    let group = &mut groups[gi];
+    ops::set_completed(group, &id, !undo);
+    save_group(paths, group)?;
+
+    let task = group.tasks.iter().find(|t| t.id == id);
+    cli.print(json!(task), || {
+        let mark = if undo { " " } else { "x" };
+        format!("[{}] {}", mark, task.map(|t| t.content.as_str()).unwrap_or_default())
+    });
+    Ok(())
+}
+
+fn cmd_mv(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let Some((target, queries)) = cli.positional().split_last() else {
+        return Err("usage: mv <id...> <group>".to_string());
+    };
+    if queries.is_empty() {
+        return Err("usage: mv <id...> <group>".to_string());
+    }
+
+    let mut groups = load_groups(paths)?;
+    let to = groups[group_index(&groups, target)?].id.clone();
+    let mut ids = Vec::new();
+    for query in queries {
+        let (gi, ti) = ops::find_task(&groups, query)?;
+        if groups[gi].id == to {
+            return Err(format!("{} is already in {:?}", query, groups[gi].name));
+        }
+        ids.push(groups[gi].tasks[ti].id.clone());
+    }
+
+    let batch = ops::move_tasks(&mut groups, &ids, &to, None)?;
+    let changed: Vec<&Group> = groups.iter().filter(|g| batch.groups.contains(&g.id)).collect();
+    store::save_groups(paths, &changed).map_err(|e| format!("cannot save: {}", e))?;
+
+    let name = changed
+        .iter()
+        .find(|g| g.id == to)
+        .map(|g| g.name.clone())
+        .unwrap_or_default();
+    cli.print(
+        json!({ "moved": batch.moved, "groups": batch.groups, "to": to }),
+        || format!("moved {} task(s) to {}", batch.moved.len(), name),
+    );
+    Ok(())
+}
+
+fn cmd_check(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let repair = cli.flag(&["--repair"]);
+    let mut groups = load_groups(paths)?;
+    let issues = check::repair(&mut groups);
+
+    if repair && !issues.is_empty() {
+        let changed: Vec<&Group> = groups
+            .iter()
+            .filter(|g| issues.iter().any(|i| i.group_id() == g.id))
+            .collect();
+        store::save_groups(paths, &changed).map_err(|e| format!("cannot save: {}", e))?;
+    }
+    cli.print(json!({ "issues": issues, "repaired": repair }), || {
+        if issues.is_empty() {
+            return "no problems found".to_string();
+        }
+        let mut lines: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        if !repair {
+            lines.push("run `check --repair` to apply these fixes".to_string());
+        }
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn cmd_audit(cli: &Cli, paths: &DataPaths) -> Result<(), String> {
+    let summary = audit::summarize(&load_groups(paths)?);
+    cli.print(json!(summary), || {
+        let row = |label: &str, t: &audit::Totals| {
+            format!(
+                "{}  est {}m  act {}m  ({} tasks, {} done)",
+                label, t.estimated_minutes, t.actual_minutes, t.tasks, t.completed
+            )
+        };
+        let mut lines = vec![row("total", &summary.total)];
+        lines.extend(summary.groups.iter().map(|g| row(&g.name, &g.totals)));
+        lines.extend(summary.tags.iter().map(|t| row(&format!("#{}", t.tag), &t.totals)));
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn cmd_migrate(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let source = match cli.positional().first() {
+        Some(path) => PathBuf::from(path),
+        None => migrate::legacy_file().ok_or("no documents folder; give the file to migrate")?,
+    };
+    let content = std::fs::read_to_string(&source).map_err(|e| format!("cannot read {}: {}", source.display(), e))?;
+    let groups = load_groups(paths)?;
+    let migration = migrate::migrate(&groups, &content);
+    if let Some(group) = &migration.group {
+        store::save_groups(paths, &[group]).map_err(|e| format!("cannot save: {}", e))?;
+    }
+    cli.print(json!(migration), || match &migration.group {
+        Some(group) => format!(
+            "imported {} tasks into group {} ({} already in the vault)",
+            migration.imported, group.id, migration.skipped
+        ),
+        None => format!(
+            "nothing to import, all {} tasks are already in the vault",
+            migration.skipped
+        ),
+    });
+    Ok(())
+}
+
+fn find_progress(items: &[ProgressEntry], query: &str) -> Result<usize, String> {
+    items
+        .iter()
+        .position(|i| i.id() == query)
+        .or_else(|| items.iter().position(|i| i.title() == query))
+        .ok_or_else(|| format!("no progress item {:?}", query))
+}
+
+fn describe(entry: &ProgressEntry) -> String {
+    match entry {
+        ProgressEntry::Progress(p) => format!(
+            "{}  {}/{}{}  today {}{}  {}",
+            p.title, p.current, p.total, p.unit, p.today_count, p.unit, p.id
+        ),
+        ProgressEntry::Counter(c) => format!(
+            "{}  {}{}  today {}{}  {}",
+            c.title, c.current, c.unit, c.today_count, c.unit, c.id
+        ),
+    }
+}
+
+fn cmd_progress(cli: &mut Cli, paths: &DataPaths) -> Result<(), String> {
+    let load = || store::load_progress(paths).map_err(|e| format!("cannot read progress: {}", e));
+
+    match cli.args.first().map(String::as_str) {
+        Some("ls") | None => {
+            let items = load()?;
+            cli.print(json!(items), || {
+                items.iter().map(describe).collect::<Vec<_>>().join("\n")
+            });
+        }
+        Some("bump") => {
+            let times = match cli.option(&["--by"])? {
+                Some(n) => n
+                    .parse::<i64>()
+                    .map_err(|_| format!("--by expects a number, got {:?}", n))?,
+                None => 1,
+            };
+            let query = cli.positional().get(1).ok_or("missing progress id or title")?.clone();
+
+            let mut items = load()?;
+            let index = find_progress(&items, &query)?;
+            items[index].bump(times, &progress::today_label());
+            store::save_progress(paths, &items).map_err(|e| format!("cannot save progress: {}", e))?;
+
+            let item = &items[index];
+            cli.print(json!(item), || describe(item));
+        }
+        Some(other) => return Err(format!("unknown progress command {:?}", other)),
+    }
+    Ok(())
+}
+
+fn run(mut cli: Cli) -> Result<(), String> {
+    if cli.help || cli.args.is_empty() {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    // Our own `--data-dir`, which only counts before the command
+    let paths = vault::paths::resolve_with(cli.data_dir.take()).ok_or("could not determine a data directory")?;
+    paths
+        .ensure()
+        .map_err(|e| format!("cannot create {}: {}", paths.root.display(), e))?;
+
+    let command = cli.args.remove(0);
+    match command.as_str() {
+        "groups" => cmd_groups(&cli, &paths),
+        "ls" => cmd_ls(&mut cli, &paths),
+        "add" => cmd_add(&mut cli, &paths),
+        "done" => cmd_done(&mut cli, &paths),
+        "mv" => cmd_mv(&mut cli, &paths),
+        "check" => cmd_check(&mut cli, &paths),
+        "audit" => cmd_audit(&cli, &paths),
+        "migrate" => cmd_migrate(&mut cli, &paths),
+        "progress" => cmd_progress(&mut cli, &paths),
+        other => Err(format!("unknown command {:?}\n\n{}", other, USAGE)),
+    }
+}
+
+fn main() -> ExitCode {
+    match Cli::parse(std::env::args().skip(1).collect()).and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("nekotick-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,230 @@
+// "Collect diagnostics": one zip with the logs, version info and the shape of
+// the vault for bug reports. Task text isn't read. File names outside the
+// fixed layout are replaced by a hash, in the layout and in the log lines,
+// where group ids (which are file names) show up. The data and home folders
+// in logged paths become `<data>` and `~`. The frontend's console lines may
+// quote task text, so only their time and level are kept; other log lines
+// are otherwise copied as written.
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use tauri::{AppHandle, State};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::vault::{self, DataPaths, NekoError};
+
+// Names that say nothing about the user's data
+const KNOWN_NAMES: &[&str] = &[
+    "tasks",
+    "progress",
+    "time-tracker",
+    "logs",
+    "outbox",
+    "settings.json",
+    ".journal.json",
+    ".nekotick",
+    "index.json",
+    "timers.json",
+    "extension.json",
+    "progress.md",
+    "time-log.md",
+    // The inbox every vault starts with
+    "default",
+    // Log files, `nekotick.log` and its rotations
+    "nekotick",
+];
+
+// Deep enough for the vault layout, shallow enough for a symlink loop
+const MAX_DEPTH: usize = 4;
+
+// `secret plan.md.1.bak` -> `3f9a02c4.md.1.bak`: the stem is hashed, the
+// suffixes stay so backups still line up with their file
+fn redact_name(name: &str) -> String {
+    if KNOWN_NAMES.contains(&name) {
+        return name.to_string();
+    }
+    let (dot, rest) = match name.strip_prefix('.') {
+        Some(rest) => (".", rest),
+        None => ("", name),
+    };
+    let (stem, suffix) = rest.split_at(rest.find('.').unwrap_or(rest.len()));
+    if KNOWN_NAMES.contains(&stem) {
+        return name.to_string();
+    }
+    let mut hasher = DefaultHasher::new();
+    stem.hash(&mut hasher);
+    format!("{}{:08x}{}", dot, hasher.finish() as u32, suffix)
+}
+
+/// Every file and folder under the vault root with its size, names redacted.
+pub fn vault_layout(paths: &DataPaths) -> String {
+    fn walk(dir: &Path, prefix: &str, depth: usize, out: &mut String) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let name = format!("{}{}", prefix, redact_name(&entry.file_name().to_string_lossy()));
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                out.push_str(&format!("{}/\n", name));
+                if depth < MAX_DEPTH {
+                    walk(&entry.path(), &format!("{}/", name), depth + 1, out);
+                }
+            } else {
+                out.push_str(&format!("{} {}\n", name, meta.len()));
+            }
+        }
+    }
+
+    let mut out = format!("source: {:?}\n", paths.source);
+    walk(&paths.root, "", 0, &mut out);
+    out
+}
+
+// Group ids a log line may mention: the stems of every file in `tasks/`,
+// backups included, longest first so no id is replaced inside another
+fn group_ids(paths: &DataPaths) -> Vec<String> {
+    let mut ids: Vec<String> = fs::read_dir(&paths.tasks)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let name = name.strip_prefix('.').unwrap_or(&name);
+            name.find(".md").map(|end| name[..end].to_string())
+        })
+        .filter(|id| !id.is_empty() && !KNOWN_NAMES.contains(&id.as_str()))
+        .collect();
+    ids.sort_by_key(|id| std::cmp::Reverse(id.len()));
+    ids.dedup();
+    ids
+}
+
+// Replaces whole-word occurrences of `word`, so the id `plan` leaves `planned` alone
+fn replace_word(text: &str, word: &str, with: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (at, _) in text.match_indices(word) {
+        let end = at + word.len();
+        if at < last
+            || text[..at].chars().next_back().is_some_and(is_word)
+            || text[end..].chars().next().is_some_and(is_word)
+        {
+            continue;
+        }
+        out.push_str(&text[last..at]);
+        out.push_str(with);
+        last = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+// `<time> <LEVEL> <target>: message` -> the target and where the message starts
+fn log_target(line: &str) -> Option<(&str, usize)> {
+    let mut words = line.split(' ').filter(|w| !w.is_empty());
+    chrono::DateTime::parse_from_rfc3339(words.next()?).ok()?;
+    log::Level::from_str(words.next()?).ok()?;
+    let target = words.next()?.strip_suffix(':')?;
+    let start = line.find(&format!(" {}:", target))? + target.len() + 2;
+    Some((target, start))
+}
+
+// Frontend messages, and the lines they run on to, become a placeholder
+fn drop_frontend_messages(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_frontend = false;
+    for line in text.split_inclusive('\n') {
+        match log_target(line) {
+            Some(("frontend", start)) => {
+                in_frontend = true;
+                out.push_str(&line[..start]);
+                out.push_str(" <redacted>\n");
+            }
+            Some(_) => {
+                in_frontend = false;
+                out.push_str(line);
+            }
+            None if in_frontend => {}
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
+/// Log text as it goes into the report: frontend messages dropped, group
+/// ids hashed as in the layout, the data and home folders replaced by
+/// `<data>` and `~`.
+pub fn redact_log(text: &str, paths: &DataPaths) -> String {
+    let mut text = drop_frontend_messages(text);
+    for id in group_ids(paths) {
+        text = replace_word(&text, &id, &redact_name(&id));
+    }
+    let root = paths.root.to_string_lossy();
+    if !root.is_empty() {
+        text = text.replace(root.as_ref(), "<data>");
+    }
+    if let Some(home) = dirs::home_dir().filter(|h| h.parent().is_some()) {
+        text = text.replace(home.to_string_lossy().as_ref(), "~");
+    }
+    text
+}
+
+/// Writes the diagnostics zip to `out`. `versions` is free-form text from
+/// the caller, who knows the app and webview versions.
+pub fn write_report(out: &Path, paths: &DataPaths, versions: &str) -> io::Result<()> {
+    let mut zip = ZipWriter::new(File::create(out)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file("versions.txt", options)?;
+    zip.write_all(versions.as_bytes())?;
+    zip.start_file("vault-layout.txt", options)?;
+    zip.write_all(vault_layout(paths).as_bytes())?;
+
+    if let Ok(entries) = fs::read_dir(paths.logs_dir()) {
+        let mut logs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect();
+        logs.sort();
+        for log in logs {
+            let name = log.file_name().unwrap_or_default().to_string_lossy();
+            zip.start_file(format!("logs/{}", name), options)?;
+            let text = String::from_utf8_lossy(&fs::read(&log)?).into_owned();
+            zip.write_all(redact_log(&text, paths).as_bytes())?;
+        }
+    }
+    zip.finish()?.sync_all()
+}
+
+// Builds the zip in the outbox folder and returns its path
+#[tauri::command]
+pub fn collect_diagnostics(app: AppHandle, paths: State<'_, DataPaths>) -> Result<PathBuf, NekoError> {
+    let versions = format!(
+        "nekotick {}\ntauri {}\nwebview {}\nos {} {}\n",
+        app.package_info().version,
+        tauri::VERSION,
+        tauri::webview_version().unwrap_or_else(|e| format!("unknown ({})", e)),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+
+    let dir = vault::settings::load(&paths).outbox_dir(&paths);
+    paths.ensure_inside(&dir).map_err(|e| NekoError::io(e, &dir))?;
+    fs::create_dir_all(&dir).map_err(|e| NekoError::io(e, &dir))?;
+    let name = format!(
+        "nekotick-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let out = dir.join(name);
+    log::info!("writing diagnostics to {}", out.display());
+    write_report(&out, &paths, &versions).map_err(|e| NekoError::io(e, &out))?;
+    Ok(out)
+}
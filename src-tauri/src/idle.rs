@@ -0,0 +1,140 @@
+// Idle detection for the task timers. An `IdleSource` says how long the user
+// has been away from the keyboard; on Linux that's the X11 screensaver
+// extension, or logind's idle hint where X11 can't see all input (Wayland).
+// `IdleWatch` turns its readings into going-away and coming-back events.
+use std::time::Duration;
+
+pub trait IdleSource {
+    /// Time since the last user input, `None` when it can't be read.
+    fn idle_for(&mut self) -> Option<Duration>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleEvent {
+    /// Idle past the threshold; `since` is the last input.
+    Away { since: i64 },
+    /// Input again after being away.
+    Back { at: i64 },
+}
+
+pub struct IdleWatch {
+    source: Box<dyn IdleSource>,
+    away: bool,
+}
+
+impl IdleWatch {
+    pub fn new(source: Box<dyn IdleSource>) -> Self {
+        IdleWatch { source, away: false }
+    }
+
+    /// Reads the source at `now` and reports a change. With no threshold
+    /// idleness is ignored, so a user marked away is taken to be back.
+    pub fn poll(&mut self, now: i64, threshold: Option<Duration>) -> Option<IdleEvent> {
+        let Some(threshold) = threshold else {
+            return std::mem::take(&mut self.away).then_some(IdleEvent::Back { at: now });
+        };
+        let idle = self.source.idle_for()?;
+        let last_input = now - i64::try_from(idle.as_millis()).unwrap_or(i64::MAX).min(now);
+        match (self.away, idle >= threshold) {
+            (false, true) => {
+                self.away = true;
+                Some(IdleEvent::Away { since: last_input })
+            }
+            (true, false) => {
+                self.away = false;
+                Some(IdleEvent::Back { at: last_input })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The best source this session offers, if any.
+pub fn detect() -> Option<Box<dyn IdleSource>> {
+    #[cfg(target_os = "linux")]
+    {
+        let x11 = || linux::X11Idle::open().map(|s| Box::new(s) as Box<dyn IdleSource>);
+        let logind = || linux::LogindIdle::open().map(|s| Box::new(s) as Box<dyn IdleSource>);
+        // XWayland only sees input sent to X clients
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            logind().or_else(x11)
+        } else {
+            x11().or_else(logind)
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use x11rb::connection::{Connection as _, RequestConnection as _};
+    use x11rb::protocol::screensaver::{self, ConnectionExt as _};
+    use x11rb::protocol::xproto::Window;
+    use x11rb::rust_connection::RustConnection;
+    use zbus::blocking::{Connection, Proxy};
+
+    use super::IdleSource;
+
+    /// The MIT-SCREEN-SAVER extension, over the same x11rb connection type
+    /// the app-usage collector uses; without X11 or the extension this is
+    /// `None` and idle detection falls back to logind.
+    pub struct X11Idle {
+        conn: RustConnection,
+        root: Window,
+    }
+
+    impl X11Idle {
+        pub fn open() -> Option<Self> {
+            let (conn, screen) = x11rb::connect(None).ok()?;
+            let root = conn.setup().roots.get(screen)?.root;
+            conn.extension_information(screensaver::X11_EXTENSION_NAME).ok()??;
+            conn.screensaver_query_version(1, 1).ok()?.reply().ok()?;
+            Some(X11Idle { conn, root })
+        }
+    }
+
+    impl IdleSource for X11Idle {
+        fn idle_for(&mut self) -> Option<Duration> {
+            let info = self.conn.screensaver_query_info(self.root).ok()?.reply().ok()?;
+            Some(Duration::from_millis(info.ms_since_user_input.into()))
+        }
+    }
+
+    /// logind's `IdleHint` for our session, which the desktop sets once its
+    /// own idle timeout passes, so this can't report less than that timeout.
+    pub struct LogindIdle {
+        session: Proxy<'static>,
+    }
+
+    impl LogindIdle {
+        pub fn open() -> Option<Self> {
+            let conn = Connection::system().ok()?;
+            let session = Proxy::new(
+                &conn,
+                "org.freedesktop.login1",
+                "/org/freedesktop/login1/session/auto",
+                "org.freedesktop.login1.Session",
+            )
+            .ok()?;
+            // Not every session is managed by logind
+            session.get_property::<bool>("IdleHint").ok()?;
+            Some(LogindIdle { session })
+        }
+    }
+
+    impl IdleSource for LogindIdle {
+        fn idle_for(&mut self) -> Option<Duration> {
+            if !self.session.get_property::<bool>("IdleHint").ok()? {
+                return Some(Duration::ZERO);
+            }
+            // Microseconds on the realtime clock
+            let since = self.session.get_property::<u64>("IdleSinceHint").ok()?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_micros();
+            let idle = now.saturating_sub(u128::from(since));
+            Some(Duration::from_micros(u64::try_from(idle).unwrap_or(u64::MAX)))
+        }
+    }
+}
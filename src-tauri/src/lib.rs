@@ -1,147 +1,126 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, LogicalPosition};
-use tauri::window::Color;
+pub mod diagnostics;
+pub mod idle;
+pub mod logging;
+pub mod overlay;
+mod storage;
+mod timer;
+mod transfer;
+pub mod usage;
+pub mod vault;
+mod watcher;
+pub mod web;
 
-// Create drag overlay window
-#[tauri::command]
-async fn create_drag_window(app: AppHandle, content: String, x: f64, y: f64, width: f64, height: f64, is_done: bool, is_dark: bool, priority: Option<String>) -> Result<(), String> {
-    // Close existing drag window if any
-    if let Some(existing) = app.get_webview_window("drag-overlay") {
-        let _ = existing.destroy();
-    }
-
-    // Theme-based colors
-    let (bg_color, border_color, text_color, text_muted) = if is_dark {
-        ("#18181b", "#3f3f46", "#fafafa", "#71717a")
-    } else {
-        ("#fff", "#e5e5e5", "#18181b", "#a1a1aa")
-    };
-    
-    // Priority colors
-    let priority_color = match priority.as_deref() {
-        Some("red") => "#ef4444",
-        Some("yellow") => "#eab308",
-        Some("purple") => "#a855f7",
-        Some("green") => "#22c55e",
-        _ => text_muted,
-    };
-    
-    let has_priority = priority.is_some() && priority.as_deref() != Some("default");
-    
-    // Style for completed tasks
-    let content_style = if is_done {
-        format!("text-decoration:line-through;color:{}", text_muted)
-    } else {
-        String::new()
-    };
-    
-    let checkbox_html = if is_done {
-        format!("<svg class=\"checkbox\" viewBox=\"0 0 16 16\"><rect x=\"0.5\" y=\"0.5\" width=\"15\" height=\"15\" rx=\"2\" fill=\"{}\" stroke=\"{}\"/><path d=\"M4 8l3 3 5-6\" stroke=\"white\" stroke-width=\"2\" fill=\"none\"/></svg>", text_color, text_color)
-    } else if has_priority {
-        format!("<div class=\"checkbox\" style=\"border:2px solid {}\"></div>", priority_color)
-    } else {
-        format!("<div class=\"checkbox\" style=\"border-color:{}\"></div>", text_muted)
-    };
-
-    // HTML content - transparent background, card fills window
-    let html = format!(r#"<!DOCTYPE html>
-<html style="background:transparent!important">
-<head>
-<style>
-*{{margin:0;padding:0;box-sizing:border-box}}
-html,body{{background:transparent!important;overflow:hidden;width:100%;height:100%}}
-body{{font-family:system-ui,-apple-system,sans-serif;display:flex}}
-.card{{
-  background:{};
-  border:1px solid {};
-  border-radius:4px;
-  padding:8px 12px;
-  display:flex;
-  align-items:start;
-  gap:8px;
-  font-size:14px;
-  color:{};
-  width:100%;
-  height:100%;
-}}
-.grip{{color:{}}}
-.checkbox{{width:16px;height:16px;border:1px solid {};border-radius:3px;flex-shrink:0;margin-top:2px}}
-.content{{flex:1;white-space:pre-wrap;word-break:break-word;overflow-wrap:anywhere}}
-</style>
-</head>
-<body style="background:transparent!important">
-<div class="card">
-<div class="grip">⋮⋮</div>
-{}
-<span class="content" style="{}">{}</span>
-</div>
-</body>
-</html>"#, bg_color, border_color, text_color, text_muted, text_muted, checkbox_html, content_style, content);
-
-    // Create transparent window - hidden first, show after setup
-    let window = WebviewWindowBuilder::new(
-        &app,
-        "drag-overlay",
-        WebviewUrl::default(),
-    )
-    .title("")
-    .inner_size(width, height)
-    .position(x - 20.0, y - (height / 2.0))
-    .decorations(false)
-    .shadow(false)
-    .background_color(Color(0, 0, 0, 0))
-    .always_on_top(true)
-    .skip_taskbar(true)
-    .resizable(false)
-    .focused(false)
-    .visible(false)
-    .build()
-    .map_err(|e| e.to_string())?;
-
-    // Ignore cursor events so drag continues
-    window.set_ignore_cursor_events(true).map_err(|e| e.to_string())?;
+use std::sync::Arc;
 
-    // Inject HTML content
-    window.eval(&format!(r#"document.write(`{}`); document.close();"#, html.replace('`', "\\`")))
-        .map_err(|e| e.to_string())?;
+use tauri::Manager;
 
-    // Show window
-    window.show().map_err(|e| e.to_string())?;
+use vault::{ErrorKind, NekoError};
 
-    Ok(())
+// Tauri only fails us on window and webview operations
+impl From<tauri::Error> for NekoError {
+    fn from(err: tauri::Error) -> Self {
+        NekoError::new(ErrorKind::WindowUnavailable, err.to_string())
+    }
 }
 
-// Update drag window position
-#[tauri::command]
-async fn update_drag_window_position(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("drag-overlay") {
-        // Get window height for vertical centering
-        let size = window.outer_size().unwrap_or(tauri::PhysicalSize::new(0, 36));
-        let half_height = (size.height as f64) / 2.0;
-        window.set_position(LogicalPosition::new(x - 20.0, y - half_height))
-            .map_err(|e| e.to_string())?;
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    if let Err(e) = start() {
+        // The logger may not be up yet, so stderr too
+        log::error!("failed to start: {}", e);
+        eprintln!("NekoTick failed to start: {}", e);
+        std::process::exit(1);
     }
-    Ok(())
 }
 
-// Destroy drag window
-#[tauri::command]
-async fn destroy_drag_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("drag-overlay") {
-        window.destroy().map_err(|e| e.to_string())?;
+fn start() -> Result<(), NekoError> {
+    // Resolve the vault before anything touches it
+    let paths = vault::paths::resolve().ok_or_else(|| NekoError::not_found("could not determine a data directory"))?;
+    paths.ensure().map_err(|e| NekoError::io(e, &paths.root))?;
+    if let Err(e) = logging::init(&paths) {
+        if cfg!(debug_assertions) {
+            eprintln!("logging is disabled: {}", e);
+        }
     }
-    Ok(())
-}
+    log::info!("starting, data directory {} ({:?})", paths.root.display(), paths.source);
+    let timers = Arc::new(timer::TimerService::load(&paths));
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
     tauri::Builder::default()
-        .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
+        .register_uri_scheme_protocol(overlay::PROTOCOL, |_ctx, _request| overlay::page_response())
+        .setup(|app| {
+            let paths = app.state::<vault::DataPaths>().inner().clone();
+
+            // External edits are a nice-to-have; keep running without them
+            let ctx = watcher::WatchContext {
+                paths,
+                tracker: app.state::<Arc<watcher::WriteTracker>>().inner().clone(),
+                bases: app.state::<Arc<storage::GroupBases>>().inner().clone(),
+            };
+            match watcher::start(app.handle(), ctx) {
+                Ok(vault_watcher) => {
+                    app.manage(vault_watcher);
+                }
+                Err(e) => log::warn!("failed to watch the vault: {}", e),
+            }
+
+            if let Err(e) = timer::start_ticker(app.handle()) {
+                log::warn!("failed to start the timer thread: {}", e);
+            }
+            if let Err(e) = usage::start(app.state::<vault::DataPaths>().inner().clone()) {
+                log::warn!("failed to start the app usage collector: {}", e);
+            }
+            let paths = app.state::<vault::DataPaths>();
+            let settings = vault::settings::load(&paths);
+            if let Err(e) = app.state::<Arc<web::WebTracker>>().apply(&paths, &settings) {
+                log::warn!("failed to listen for the browser extension: {}", e);
+            }
+
+            // Created up front so the first drag doesn't wait for a new webview
+            if let Err(e) = overlay::prepare(app.handle()) {
+                log::warn!("failed to create the drag overlay: {}", e);
+            }
+            Ok(())
+        })
+        .manage(paths)
+        .manage(Arc::new(watcher::WriteTracker::default()))
+        .manage(Arc::new(storage::GroupBases::default()))
+        .manage(overlay::DragOverlay::default())
+        .manage(transfer::DroppedFiles::default())
+        .manage(timers)
+        .manage(Arc::new(web::WebTracker::default()))
+        .on_window_event(transfer::on_window_event)
         .invoke_handler(tauri::generate_handler![
-            create_drag_window,
-            update_drag_window_position,
-            destroy_drag_window
+            overlay::show_drag_overlay,
+            overlay::show_selection_drag_overlay,
+            overlay::hide_drag_overlay,
+            storage::get_data_paths,
+            storage::load_groups,
+            storage::save_group,
+            storage::adopt_group,
+            storage::delete_group,
+            storage::move_tasks,
+            storage::check_vault,
+            storage::repair_vault,
+            storage::migrate_legacy,
+            storage::time_audit,
+            storage::get_settings,
+            storage::save_settings,
+            storage::load_progress,
+            storage::save_progress,
+            storage::load_time_log,
+            timer::timer_status,
+            timer::timer_start,
+            timer::timer_stop,
+            timer::pomodoro_start,
+            timer::pomodoro_stop,
+            timer::timer_resolve_idle,
+            web::web_endpoint,
+            transfer::export_drag,
+            transfer::import_files,
+            logging::log_frontend,
+            diagnostics::collect_diagnostics
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .run(tauri::generate_context!())?;
+    Ok(())
 }
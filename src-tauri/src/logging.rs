@@ -0,0 +1,169 @@
+// Log files in `<vault>/logs`. Rust code logs through the `log` macros and
+// the frontend forwards its console lines with `log_frontend`; both end up
+// in `nekotick.log`, which rotates to `nekotick.1.log`, `nekotick.2.log`, ...
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::vault::{DataPaths, NekoError};
+
+pub const LOG_FILE: &str = "nekotick.log";
+/// Overrides the level, e.g. `NEKOTICK_LOG=debug`.
+pub const ENV_LOG: &str = "NEKOTICK_LOG";
+
+const MAX_BYTES: u64 = 1024 * 1024;
+const KEEP: usize = 4;
+// A runaway frontend loop shouldn't fill the disk one huge line at a time
+const MAX_FRONTEND_LINE: usize = 4096;
+
+/// `logs/nekotick.log` -> `logs/nekotick.<generation>.log`
+pub fn rotated_path(dir: &Path, generation: usize) -> PathBuf {
+    dir.join(format!("nekotick.{}.log", generation))
+}
+
+/// An append-only log file that is rotated once it passes `max_bytes`,
+/// keeping `keep` older generations.
+pub struct RotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Option<File>,
+    len: u64,
+}
+
+impl RotatingFile {
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> Self {
+        RotatingFile {
+            dir: dir.into(),
+            max_bytes,
+            keep,
+            file: None,
+            len: 0,
+        }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE)
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let needed = line.len() as u64 + 1;
+        if self.file.is_some() && self.len > 0 && self.len + needed > self.max_bytes {
+            self.rotate()?;
+        }
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new().create(true).append(true).open(self.path())?;
+                self.len = file.metadata()?.len();
+                self.file.insert(file)
+            }
+        };
+        file.write_all(line.as_bytes())?;
+        file.write_all(b"\n")?;
+        self.len += needed;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        if self.keep == 0 {
+            return fs::remove_file(self.path());
+        }
+        let _ = fs::remove_file(rotated_path(&self.dir, self.keep));
+        for generation in (1..self.keep).rev() {
+            let from = rotated_path(&self.dir, generation);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.dir, generation + 1))?;
+            }
+        }
+        fs::rename(self.path(), rotated_path(&self.dir, 1))
+    }
+}
+
+struct FileLogger {
+    file: Mutex<RotatingFile>,
+    level: LevelFilter,
+}
+
+impl FileLogger {
+    // Our own crate and the frontend log at the configured level; the
+    // dependencies only get to report problems
+    fn allows(&self, metadata: &Metadata) -> bool {
+        let ours = metadata.target().starts_with("nekotick") || metadata.target() == "frontend";
+        metadata.level() <= if ours { self.level } else { LevelFilter::Warn }
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.allows(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.allows(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}: {}",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if cfg!(debug_assertions) {
+            eprintln!("{}", line);
+        }
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the file logger and a panic hook that logs before the default
+/// one runs. Called once, first thing in `run()`.
+pub fn init(paths: &DataPaths) -> Result<(), NekoError> {
+    let dir = paths.logs_dir();
+    fs::create_dir_all(&dir).map_err(|e| NekoError::io(e, &dir))?;
+    paths.ensure_inside(&dir).map_err(|e| NekoError::io(e, &dir))?;
+
+    let default = if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+    let level = std::env::var(ENV_LOG)
+        .ok()
+        .and_then(|v| LevelFilter::from_str(&v).ok())
+        .unwrap_or(default);
+    let logger = FileLogger {
+        file: Mutex::new(RotatingFile::new(dir, MAX_BYTES, KEEP)),
+        level,
+    };
+    log::set_boxed_logger(Box::new(logger)).map_err(|e| NekoError::internal(e.to_string()))?;
+    log::set_max_level(level.max(LevelFilter::Warn));
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!("{}", info);
+        previous(info);
+    }));
+    Ok(())
+}
+
+// Writes one console line from the frontend to the log
+#[tauri::command]
+pub fn log_frontend(level: String, message: String) {
+    let level = Level::from_str(&level).unwrap_or(Level::Info);
+    let message = match message.char_indices().nth(MAX_FRONTEND_LINE) {
+        Some((cut, _)) => format!("{}...", &message[..cut]),
+        None => message,
+    };
+    log::log!(target: "frontend", level, "{}", message);
+}
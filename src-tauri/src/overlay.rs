@@ -0,0 +1,274 @@
+// Drag overlay: a small always-on-top window that follows the cursor while a
+// task (and its subtasks) or a multi-selection is dragged.
+//
+// The window is created hidden at startup, well before the first drag, and
+// reused for every drag. Its page is fixed (overlay.html, served from our own
+// protocol) and task data reaches it as a `RENDER_EVENT`, which it only
+// inserts with `textContent`. Its capability (capabilities/overlay.json)
+// lets it listen for events and nothing else. From show to hide a thread
+// moves the window to the cursor.
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::window::Color;
+use tauri::{
+    AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, State, Url, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+
+use crate::vault::{self, DataPaths, Group, NekoError, Priority};
+
+pub const WINDOW_LABEL: &str = "drag-overlay";
+/// URI scheme that serves the overlay page.
+pub const PROTOCOL: &str = "nekotick-overlay";
+/// Sent to the overlay window with the `OverlayData` to show.
+pub const RENDER_EVENT: &str = "drag-overlay-render";
+
+// How often the window is moved to the cursor while dragging
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(8);
+
+const PAGE: &str = include_str!("overlay.html");
+
+/// Rows shown before the rest is summarised as "还有 N 项".
+pub const MAX_ROWS: usize = 8;
+
+// Layout of overlay.html, used to size the window to its content
+const LINE_HEIGHT: f64 = 20.0;
+const ROW_GAP: f64 = 4.0;
+const FOOTER_HEIGHT: f64 = 18.0;
+const PADDING: f64 = 8.0;
+const BORDER: f64 = 1.0;
+const DEFAULT_WIDTH: f64 = 350.0;
+const MIN_WIDTH: f64 = 160.0;
+const MAX_WIDTH: f64 = 640.0;
+// The cursor sits on the grip of the first row
+const ANCHOR_X: f64 = 20.0;
+const ANCHOR_Y: f64 = PADDING + BORDER + LINE_HEIGHT / 2.0;
+
+/// One row of the overlay: a dragged task or one of its subtasks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DragItem {
+    pub content: String,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    /// Nesting below the dragged task, which is 0.
+    #[serde(default)]
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayData {
+    pub items: Vec<DragItem>,
+    /// Items left out after `MAX_ROWS`.
+    pub more: usize,
+    /// Number of selected tasks, shown as a badge when above one.
+    pub count: usize,
+    pub dark: bool,
+}
+
+impl OverlayData {
+    pub fn new(mut items: Vec<DragItem>, dark: bool) -> Self {
+        let more = items.len().saturating_sub(MAX_ROWS);
+        items.truncate(MAX_ROWS);
+        OverlayData {
+            items,
+            more,
+            count: 1,
+            dark,
+        }
+    }
+
+    /// Height of the card in logical pixels. Rows never wrap, so this only
+    /// depends on the number of rows and explicit line breaks.
+    pub fn height(&self) -> f64 {
+        let lines: usize = self.items.iter().map(|i| i.content.lines().count().max(1)).sum();
+        let mut blocks = self.items.len();
+        let mut height = lines as f64 * LINE_HEIGHT;
+        if self.more > 0 {
+            height += FOOTER_HEIGHT;
+            blocks += 1;
+        }
+        height += blocks.saturating_sub(1) as f64 * ROW_GAP;
+        height + 2.0 * (PADDING + BORDER)
+    }
+}
+
+/// Rows for a multi-selection: each selected task followed by its subtasks,
+/// plus the number of separately selected tasks for the badge.
+pub fn selection_items(groups: &[Group], ids: &[String]) -> Result<(Vec<DragItem>, usize), NekoError> {
+    fn walk(group: &Group, id: &str, depth: usize, items: &mut Vec<DragItem>) {
+        let Some(task) = group.tasks.iter().find(|t| t.id == id) else {
+            return;
+        };
+        items.push(DragItem {
+            content: task.content.clone(),
+            done: task.completed,
+            priority: task.priority,
+            depth,
+        });
+        let mut children: Vec<_> = group
+            .tasks
+            .iter()
+            .filter(|t| t.parent_id.as_deref() == Some(id))
+            .collect();
+        children.sort_by_key(|t| t.order);
+        for child in children {
+            // Guard against `parent:` cycles in hand-edited files
+            if depth < 64 {
+                walk(group, &child.id, depth + 1, items);
+            }
+        }
+    }
+
+    let roots = vault::ops::selection_roots(groups, ids)?;
+    let mut items = Vec::new();
+    for (gi, id) in &roots {
+        walk(&groups[*gi], id, 0, &mut items);
+    }
+    Ok((items, roots.len()))
+}
+
+/// Card width: the dragged element's width if known, within sane bounds.
+pub fn overlay_width(requested: Option<f64>) -> f64 {
+    match requested {
+        Some(w) if w.is_finite() => w.clamp(MIN_WIDTH, MAX_WIDTH),
+        _ => DEFAULT_WIDTH,
+    }
+}
+
+/// Response for every request on `PROTOCOL`: the page has no other assets.
+pub fn page_response() -> tauri::http::Response<&'static [u8]> {
+    tauri::http::Response::builder()
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(PAGE.as_bytes())
+        .unwrap_or_default()
+}
+
+fn page_url() -> Url {
+    // Custom protocols are exposed as http://<scheme>.localhost on Windows
+    let url = if cfg!(any(windows, target_os = "android")) {
+        format!("http://{}.localhost/", PROTOCOL)
+    } else {
+        format!("{}://localhost/", PROTOCOL)
+    };
+    Url::parse(&url).expect("valid overlay url")
+}
+
+/// Bumped whenever a drag starts or ends; a follow loop stops as soon as
+/// the value it started with is gone.
+#[derive(Default)]
+pub struct DragOverlay {
+    session: AtomicU64,
+}
+
+/// Creates the hidden overlay window. Called once from `setup`, and again
+/// if the window was closed behind our back.
+pub fn prepare(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    let window = WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::CustomProtocol(page_url()))
+        .title("")
+        .inner_size(DEFAULT_WIDTH, OverlayData::new(Vec::new(), false).height())
+        .decorations(false)
+        .shadow(false)
+        .background_color(Color(0, 0, 0, 0))
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(false)
+        .visible(false)
+        .build()?;
+    // Ignore cursor events so drag continues
+    window.set_ignore_cursor_events(true)?;
+    Ok(window)
+}
+
+fn overlay_window(app: &AppHandle) -> Result<WebviewWindow, NekoError> {
+    match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => Ok(window),
+        None => Ok(prepare(app)?),
+    }
+}
+
+// Top-left corner that puts the anchor under the cursor
+fn anchored(window: &WebviewWindow, cursor: PhysicalPosition<f64>) -> PhysicalPosition<f64> {
+    let scale = window.scale_factor().unwrap_or(1.0);
+    PhysicalPosition::new(cursor.x - ANCHOR_X * scale, cursor.y - ANCHOR_Y * scale)
+}
+
+// Runs until the drag that started it ends
+fn follow_cursor(app: AppHandle, window: WebviewWindow, session: u64) -> std::io::Result<()> {
+    thread::Builder::new().name("nekotick-overlay".into()).spawn(move || {
+        let overlay = app.state::<DragOverlay>();
+        let mut last = None;
+        while overlay.session.load(Ordering::SeqCst) == session {
+            // Not every platform reports the cursor (Wayland); then the card stays put
+            if let Ok(cursor) = app.cursor_position() {
+                if last != Some(cursor) {
+                    let _ = window.set_position(anchored(&window, cursor));
+                    last = Some(cursor);
+                }
+            }
+            thread::sleep(FOLLOW_INTERVAL);
+        }
+    })?;
+    Ok(())
+}
+
+fn show_overlay(app: &AppHandle, data: &OverlayData, width: Option<f64>) -> Result<(), NekoError> {
+    if data.items.is_empty() {
+        return Err(NekoError::invalid("nothing to drag"));
+    }
+    let window = overlay_window(app)?;
+    app.emit_to(WINDOW_LABEL, RENDER_EVENT, data)?;
+    window.set_size(LogicalSize::new(overlay_width(width), data.height()))?;
+    if let Ok(cursor) = app.cursor_position() {
+        let _ = window.set_position(anchored(&window, cursor));
+    }
+    window.show()?;
+
+    let session = app.state::<DragOverlay>().session.fetch_add(1, Ordering::SeqCst) + 1;
+    follow_cursor(app.clone(), window, session)?;
+    Ok(())
+}
+
+// Show the overlay for one dragged task and its subtasks
+#[tauri::command]
+pub async fn show_drag_overlay(
+    app: AppHandle,
+    items: Vec<DragItem>,
+    width: Option<f64>,
+    is_dark: bool,
+) -> Result<(), NekoError> {
+    show_overlay(&app, &OverlayData::new(items, is_dark), width)
+}
+
+// Show the overlay for several selected tasks, read from the vault
+#[tauri::command]
+pub async fn show_selection_drag_overlay(
+    app: AppHandle,
+    paths: State<'_, DataPaths>,
+    task_ids: Vec<String>,
+    width: Option<f64>,
+    is_dark: bool,
+) -> Result<(), NekoError> {
+    let groups = vault::store::load_groups(&paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    let (items, count) = selection_items(&groups, &task_ids)?;
+    let mut data = OverlayData::new(items, is_dark);
+    data.count = count;
+    show_overlay(&app, &data, width)
+}
+
+// Hide the overlay and stop following the cursor
+#[tauri::command]
+pub async fn hide_drag_overlay(app: AppHandle, overlay: State<'_, DragOverlay>) -> Result<(), NekoError> {
+    overlay.session.fetch_add(1, Ordering::SeqCst);
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        window.hide()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,305 @@
+// Commands reading and writing the vault for the frontend, which has no
+// filesystem access of its own
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::vault::migrate::Migration;
+use crate::vault::{self, DataPaths, DayTimeData, Group, Issue, NekoError, ProgressEntry, Settings};
+use crate::watcher::WriteTracker;
+use crate::web::WebTracker;
+
+/// The last version of each group the frontend has seen: the merge base
+/// when the file changed on disk before the next save.
+#[derive(Default)]
+pub struct GroupBases(Mutex<HashMap<String, Group>>);
+
+impl GroupBases {
+    pub fn remember(&self, group: &Group) {
+        if let Ok(mut bases) = self.0.lock() {
+            bases.insert(group.id.clone(), group.clone());
+        }
+    }
+
+    pub fn forget(&self, id: &str) {
+        if let Ok(mut bases) = self.0.lock() {
+            bases.remove(id);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_data_paths(paths: State<'_, DataPaths>) -> DataPaths {
+    paths.inner().clone()
+}
+
+#[tauri::command]
+pub fn load_groups(paths: State<'_, DataPaths>, bases: State<'_, Arc<GroupBases>>) -> Result<Vec<Group>, NekoError> {
+    let groups = vault::store::load_groups(&paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    for group in &groups {
+        bases.remember(group);
+    }
+    Ok(groups)
+}
+
+// Atomic replace with `.bak` rotation; replaces the frontend's direct fs write.
+// If the file changed on disk since the frontend loaded it, the two versions
+// are merged and the merged group is returned so the UI can adopt it.
+#[tauri::command]
+pub fn save_group(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    group: Group,
+) -> Result<Option<Group>, NekoError> {
+    // Held for the whole save so two saves of one group can't interleave
+    let mut bases = bases.0.lock()?;
+    let path = paths.group_file(&group.id);
+    // A batch interrupted earlier lands first, so it merges in as theirs
+    vault::store::recover(&paths).map_err(|e| NekoError::io(e, &paths.journal_file()))?;
+
+    let mut group = group;
+    let mut merged = None;
+    if path.exists() {
+        // A file we can't read is refused rather than overwritten
+        let theirs = vault::store::load_group(&paths, &group.id).map_err(|e| NekoError::io(e, &path))?;
+        // A file the frontend never loaded merges as if it started out empty
+        let base = match bases.get(&group.id) {
+            Some(base) => base.clone(),
+            None => Group {
+                tasks: Vec::new(),
+                ..theirs.clone()
+            },
+        };
+        // Compare the canonical text so task order in memory doesn't matter
+        if vault::tasks::serialize_group(&theirs) != vault::tasks::serialize_group(&base) {
+            group = vault::merge::merge_groups(&base, &group, &theirs).group;
+            merged = Some(group.clone());
+        }
+    }
+
+    for (id, content) in vault::store::group_files(&paths, &[&group]).map_err(|e| NekoError::io(e, &path))? {
+        // Record first so the watcher can't see the rename before we do
+        tracker.record(&path, content.as_bytes());
+        vault::store::write_group_file(&paths, &id, &content).map_err(|e| NekoError::io(e, &path))?;
+    }
+    bases.insert(group.id.clone(), group);
+
+    Ok(merged)
+}
+
+// The frontend took over a version the watcher reported, so later saves
+// merge against it. Until then they still merge against what it had before.
+#[tauri::command]
+pub fn adopt_group(bases: State<'_, Arc<GroupBases>>, group: Group) {
+    bases.remember(&group);
+}
+
+// Deleting the file also makes the watcher report the group as removed
+#[tauri::command]
+pub fn delete_group(
+    paths: State<'_, DataPaths>,
+    bases: State<'_, Arc<GroupBases>>,
+    group_id: String,
+) -> Result<(), NekoError> {
+    let mut bases = bases.0.lock()?;
+    vault::store::delete_group(&paths, &group_id).map_err(|e| NekoError::io(e, &paths.group_file(&group_id)))?;
+    bases.remove(&group_id);
+    Ok(())
+}
+
+/// Runs a backend-side edit on freshly loaded groups and saves the groups it
+/// reports as changed in one journaled write, under the save lock. Returns
+/// the changed groups for the UI to adopt.
+pub fn edit_groups<F>(
+    paths: &DataPaths,
+    tracker: &WriteTracker,
+    bases: &GroupBases,
+    edit: F,
+) -> Result<Vec<Group>, NekoError>
+where
+    F: FnOnce(&mut Vec<Group>) -> Result<Vec<String>, NekoError>,
+{
+    let mut bases = bases.0.lock()?;
+    let mut groups = vault::store::load_groups(paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    let changed_ids = edit(&mut groups)?;
+
+    let changed: Vec<Group> = groups.into_iter().filter(|g| changed_ids.contains(&g.id)).collect();
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+    let refs: Vec<&Group> = changed.iter().collect();
+    let files = vault::store::group_files(paths, &refs).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    for (id, content) in &files {
+        tracker.record(&paths.group_file(id), content.as_bytes());
+    }
+    vault::store::write_group_files(paths, &files).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    for group in &changed {
+        bases.insert(group.id.clone(), group.clone());
+    }
+    Ok(changed)
+}
+
+/// Saves the groups `edit` loaded and changed itself, under the save lock.
+/// Unlike `edit_groups` the merge bases stay where they were: a save the
+/// frontend already sent must still merge against the version it had, and
+/// the frontend adopts the new one with `adopt_group` once it applied it.
+pub fn edit_unadopted<F>(
+    paths: &DataPaths,
+    tracker: &WriteTracker,
+    bases: &GroupBases,
+    edit: F,
+) -> Result<Vec<Group>, NekoError>
+where
+    F: FnOnce() -> Vec<Group>,
+{
+    let _lock = bases.0.lock()?;
+    vault::store::recover(paths).map_err(|e| NekoError::io(e, &paths.journal_file()))?;
+    let changed = edit();
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+    let refs: Vec<&Group> = changed.iter().collect();
+    let files = vault::store::group_files(paths, &refs).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    for (id, content) in &files {
+        tracker.record(&paths.group_file(id), content.as_bytes());
+    }
+    vault::store::write_group_files(paths, &files).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    Ok(changed)
+}
+
+// Moves a multi-selection (with subtasks) between groups in one journaled
+// write and returns every group that changed for the UI to adopt.
+#[tauri::command]
+pub fn move_tasks(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    task_ids: Vec<String>,
+    target_group_id: String,
+    index: Option<usize>,
+) -> Result<Vec<Group>, NekoError> {
+    edit_groups(&paths, &tracker, &bases, |groups| {
+        Ok(vault::ops::move_tasks(groups, &task_ids, &target_group_id, index)?.groups)
+    })
+}
+
+// Reports duplicate ids, missing parents, cycles and bad orders; writes nothing
+#[tauri::command]
+pub fn check_vault(paths: State<'_, DataPaths>) -> Result<Vec<Issue>, NekoError> {
+    let groups = vault::store::load_groups(&paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    Ok(vault::check::check(&groups))
+}
+
+/// What `repair_vault` fixed and the groups it rewrote.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRepair {
+    pub issues: Vec<Issue>,
+    pub groups: Vec<Group>,
+}
+
+// Applies the repairs `check_vault` reported in one journaled write
+#[tauri::command]
+pub fn repair_vault(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+) -> Result<VaultRepair, NekoError> {
+    let mut issues = Vec::new();
+    let groups = edit_groups(&paths, &tracker, &bases, |groups| {
+        issues = vault::check::repair(groups);
+        Ok(groups
+            .iter()
+            .filter(|g| issues.iter().any(|i| i.group_id() == g.id))
+            .map(|g| g.id.clone())
+            .collect())
+    })?;
+    for issue in &issues {
+        log::info!("repaired {}", issue);
+    }
+    Ok(VaultRepair { issues, groups })
+}
+
+// Imports the legacy `Documents/NekoTick/nekotick.md` as a new group. The
+// old file is read, never written; tasks already in the vault are skipped.
+#[tauri::command]
+pub fn migrate_legacy(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+) -> Result<Migration, NekoError> {
+    let source = vault::migrate::legacy_file().ok_or_else(|| NekoError::not_found("no documents folder"))?;
+    let content = std::fs::read_to_string(&source).map_err(|e| NekoError::io(e, &source))?;
+    let mut migration = None;
+    edit_groups(&paths, &tracker, &bases, |groups| {
+        let result = vault::migrate::migrate(groups, &content);
+        let changed = result.group.iter().map(|g| g.id.clone()).collect();
+        groups.extend(result.group.clone());
+        migration = Some(result);
+        Ok(changed)
+    })?;
+    let migration = migration.ok_or_else(|| NekoError::internal("migration did not run"))?;
+    log::info!(
+        "migrated {}: {} imported, {} skipped",
+        source.display(),
+        migration.imported,
+        migration.skipped
+    );
+    Ok(migration)
+}
+
+// Estimate-vs-actual totals per group and per `#tag`
+#[tauri::command]
+pub fn time_audit(paths: State<'_, DataPaths>) -> Result<vault::audit::Summary, NekoError> {
+    let groups = vault::store::load_groups(&paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    Ok(vault::audit::summarize(&groups))
+}
+
+#[tauri::command]
+pub fn get_settings(paths: State<'_, DataPaths>) -> Settings {
+    vault::settings::load(&paths)
+}
+
+// Switching the Markdown style rewrites every group file right away, so the
+// vault is never left half in one format and half in the other
+#[tauri::command]
+pub fn save_settings(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    web: State<'_, Arc<WebTracker>>,
+    settings: Settings,
+) -> Result<(), NekoError> {
+    let restyle = vault::settings::load(&paths).clean_markdown != settings.clean_markdown;
+    vault::settings::save(&paths, &settings).map_err(|e| NekoError::io(e, &paths.settings_file()))?;
+    if restyle {
+        let groups = edit_groups(&paths, &tracker, &bases, |groups| {
+            Ok(groups.iter().map(|g| g.id.clone()).collect())
+        })?;
+        log::info!(
+            "rewrote {} group files as {} Markdown",
+            groups.len(),
+            if settings.clean_markdown { "clean" } else { "annotated" }
+        );
+    }
+    web.apply(&paths, &settings)
+        .map_err(|e| NekoError::io(e, &paths.extension_file()))
+}
+
+#[tauri::command]
+pub fn load_progress(paths: State<'_, DataPaths>) -> Result<Vec<ProgressEntry>, NekoError> {
+    vault::store::load_progress(&paths).map_err(|e| NekoError::io(e, &paths.progress_file()))
+}
+
+#[tauri::command]
+pub fn save_progress(paths: State<'_, DataPaths>, items: Vec<ProgressEntry>) -> Result<(), NekoError> {
+    vault::store::save_progress(&paths, &items).map_err(|e| NekoError::io(e, &paths.progress_file()))
+}
+
+#[tauri::command]
+pub fn load_time_log(paths: State<'_, DataPaths>) -> Result<Vec<DayTimeData>, NekoError> {
+    vault::store::load_time_log(&paths).map_err(|e| NekoError::io(e, &paths.time_log_file()))
+}
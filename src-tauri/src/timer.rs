@@ -0,0 +1,316 @@
+// The task timer service. `TimerService` owns the running timers and is
+// managed as state for the whole app; a background thread emits
+// `timer-tick` every second while one runs, whether or not the window is
+// visible. Stopping a timer logs its session into the task's group file,
+// the only file it reads and rewrites.
+// The same thread moves pomodoros on when a phase runs out and pauses the
+// timers when the user goes idle, see `idle.rs`.
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::idle::{self, IdleEvent, IdleWatch};
+use crate::storage::{edit_unadopted, GroupBases};
+use crate::vault::pomodoro::Pomodoro;
+use crate::vault::timer::{self, IdleBreak, IdleChoice, Outcome, RunningTimer, Timers};
+use crate::vault::{self, DataPaths, Group, NekoError};
+use crate::watcher::WriteTracker;
+
+pub const TIMER_TICK: &str = "timer-tick";
+/// Sent with a `TimerUpdate` when the backend itself changed the timers.
+pub const TIMER_UPDATE: &str = "timer-update";
+
+const TICK: Duration = Duration::from_secs(1);
+// Ticks between idle checks
+const IDLE_POLL: u64 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerStatus {
+    pub running: Vec<RunningTimer>,
+    pub pomodoro: Pomodoro,
+    /// Timers paused for an idle user; once they're back the UI asks what
+    /// the time away was.
+    pub idle: Option<IdleBreak>,
+    /// The backend clock, so the UI can show elapsed time without drift.
+    pub now: i64,
+}
+
+/// The timers after a start or stop, and the groups whose logs changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerUpdate {
+    pub status: TimerStatus,
+    pub groups: Vec<Group>,
+}
+
+pub struct TimerService {
+    timers: Mutex<Timers>,
+    /// The group each timed task was last found in.
+    owners: Mutex<HashMap<String, String>>,
+}
+
+impl TimerService {
+    /// Resumes whatever was running when the app last exited.
+    pub fn load(paths: &DataPaths) -> Self {
+        let timers = timer::load(paths);
+        for running in &timers.running {
+            log::info!(
+                "resuming timer for {} started at {}",
+                running.task_id,
+                running.started_at
+            );
+        }
+        TimerService {
+            timers: Mutex::new(timers),
+            owners: Mutex::default(),
+        }
+    }
+
+    /// Fails unless `task_id` exists, and remembers its group for logging.
+    pub fn find(&self, paths: &DataPaths, task_id: &str) -> Result<(), NekoError> {
+        let mut owners = self.owners.lock()?;
+        let group = timer::load_owner(paths, task_id, owners.get(task_id).map(String::as_str))?;
+        owners.insert(task_id.to_string(), group.id);
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<TimerStatus, NekoError> {
+        let timers = self.timers.lock()?;
+        Ok(status_of(&timers))
+    }
+
+    /// Whether a pomodoro phase has run out.
+    pub fn is_due(&self) -> Result<bool, NekoError> {
+        Ok(self.timers.lock()?.is_due(vault::now_millis()))
+    }
+
+    /// Whether idleness matters: a timer runs or waits for the user's return.
+    pub fn watches_idle(&self) -> Result<bool, NekoError> {
+        let timers = self.timers.lock()?;
+        Ok(!timers.running.is_empty() || timers.idle.as_ref().is_some_and(|i| i.returned_at.is_none()))
+    }
+
+    /// Applies `change` to the timers, logs the sessions it stopped and
+    /// saves both. The group files are written first: if we die before the
+    /// timers are saved, the timer resumes and `log_interval` later replaces
+    /// the session instead of counting it twice. Finished pomodoros are
+    /// counted last, so a crash can lose one but never count it twice.
+    pub fn update<F>(
+        &self,
+        paths: &DataPaths,
+        tracker: &WriteTracker,
+        bases: &GroupBases,
+        change: F,
+    ) -> Result<TimerUpdate, NekoError>
+    where
+        F: FnOnce(&mut Timers) -> Result<Outcome, NekoError>,
+    {
+        let mut timers = self.timers.lock()?;
+        let mut next = timers.clone();
+        let outcome = change(&mut next)?;
+        let groups = if outcome.sessions.is_empty() {
+            Vec::new()
+        } else {
+            let mut owners = self.owners.lock()?;
+            edit_unadopted(paths, tracker, bases, || {
+                timer::log_sessions(paths, &outcome.sessions, &mut owners)
+            })?
+        };
+        if next != *timers {
+            timer::save(paths, &next).map_err(|e| NekoError::io(e, &paths.timers_file()))?;
+            *timers = next;
+        }
+        vault::store::record_pomodoros(paths, &outcome.pomodoros)
+            .map_err(|e| NekoError::io(e, &paths.time_log_file()))?;
+        Ok(TimerUpdate {
+            status: status_of(&timers),
+            groups,
+        })
+    }
+}
+
+fn status_of(timers: &Timers) -> TimerStatus {
+    TimerStatus {
+        running: timers.running.clone(),
+        pomodoro: timers.pomodoro.clone(),
+        idle: timers.idle.clone(),
+        now: vault::now_millis(),
+    }
+}
+
+// Finishes whatever pomodoro phase ran out and tells the window
+fn advance(app: &AppHandle, service: &TimerService) -> Result<(), NekoError> {
+    let paths = app.state::<DataPaths>();
+    let tracker = app.state::<Arc<WriteTracker>>();
+    let bases = app.state::<Arc<GroupBases>>();
+    let update = service.update(&paths, &tracker, &bases, |timers| {
+        Ok(timers.advance(vault::now_millis()))
+    })?;
+    let _ = app.emit_to("main", TIMER_UPDATE, update);
+    Ok(())
+}
+
+// Pauses the timers when the user goes idle and, on their return, resumes
+// them right away or leaves the break for the UI to settle
+fn check_idle(app: &AppHandle, service: &TimerService, watch: &mut IdleWatch) -> Result<(), NekoError> {
+    if !service.watches_idle()? {
+        return Ok(());
+    }
+    let paths = app.state::<DataPaths>();
+    let config = vault::settings::load(&paths).idle;
+    let threshold = (config.minutes > 0).then(|| Duration::from_secs(u64::from(config.minutes) * 60));
+    let Some(event) = watch.poll(vault::now_millis(), threshold) else {
+        return Ok(());
+    };
+    log::info!("user idle state changed: {:?}", event);
+    let tracker = app.state::<Arc<WriteTracker>>();
+    let bases = app.state::<Arc<GroupBases>>();
+    let update = service.update(&paths, &tracker, &bases, |timers| {
+        Ok(match event {
+            IdleEvent::Away { since } => timers.go_idle(since),
+            IdleEvent::Back { at } if timers.come_back(at) && config.auto_pause => {
+                timers.resolve_idle(IdleChoice::Discard, at)
+            }
+            IdleEvent::Back { .. } => Outcome::default(),
+        })
+    })?;
+    let _ = app.emit_to("main", TIMER_UPDATE, update);
+    Ok(())
+}
+
+/// Emits `timer-tick` with the status every second while a timer or a
+/// pomodoro runs.
+pub fn start_ticker(app: &AppHandle) -> std::io::Result<()> {
+    let app = app.clone();
+    thread::Builder::new().name("nekotick-timer".into()).spawn(move || {
+        // Opened on this thread, which is the only one that reads it
+        let mut idle = idle::detect().map(IdleWatch::new);
+        if idle.is_none() {
+            log::warn!("idle detection is unavailable, timers won't pause");
+        }
+        let mut ticks: u64 = 0;
+        loop {
+            thread::sleep(TICK);
+            ticks += 1;
+            tick(&app, idle.as_mut().filter(|_| ticks.is_multiple_of(IDLE_POLL)));
+        }
+    })?;
+    Ok(())
+}
+
+fn tick(app: &AppHandle, idle: Option<&mut IdleWatch>) {
+    let service = app.state::<Arc<TimerService>>();
+    if let Some(watch) = idle {
+        if let Err(e) = check_idle(app, &service, watch) {
+            log::warn!("idle check failed: {}", e);
+        }
+    }
+    if service.is_due().unwrap_or(false) {
+        if let Err(e) = advance(app, &service) {
+            log::warn!("failed to finish a pomodoro phase: {}", e);
+        }
+    }
+    match service.status() {
+        Ok(status) if !status.running.is_empty() || status.pomodoro.session.is_some() => {
+            let _ = app.emit_to("main", TIMER_TICK, status);
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("timer tick failed: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn timer_status(service: State<'_, Arc<TimerService>>) -> Result<TimerStatus, NekoError> {
+    service.status()
+}
+
+// Starts timing a task; unless `concurrent`, other running timers stop first
+#[tauri::command]
+pub fn timer_start(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    service: State<'_, Arc<TimerService>>,
+    task_id: String,
+    concurrent: Option<bool>,
+) -> Result<TimerUpdate, NekoError> {
+    service.find(&paths, &task_id)?;
+    service.update(&paths, &tracker, &bases, |timers| {
+        Ok(timers
+            .start(&task_id, vault::now_millis(), concurrent.unwrap_or(false))
+            .into())
+    })
+}
+
+// Stops a task's timer and logs the session; stopping an idle task is a no-op
+#[tauri::command]
+pub fn timer_stop(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    service: State<'_, Arc<TimerService>>,
+    task_id: String,
+) -> Result<TimerUpdate, NekoError> {
+    service.update(&paths, &tracker, &bases, |timers| {
+        Ok(timers
+            .stop(&task_id, vault::now_millis())
+            .map(|interval| (task_id.clone(), interval))
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into())
+    })
+}
+
+// Starts a pomodoro with the lengths from the settings, on a task or none
+#[tauri::command]
+pub fn pomodoro_start(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    service: State<'_, Arc<TimerService>>,
+    task_id: Option<String>,
+) -> Result<TimerUpdate, NekoError> {
+    let config = vault::settings::load(&paths).pomodoro;
+    if let Some(id) = &task_id {
+        service.find(&paths, id)?;
+    }
+    service.update(&paths, &tracker, &bases, |timers| {
+        Ok(timers.start_pomodoro(task_id.as_deref(), vault::now_millis(), config))
+    })
+}
+
+// Abandons the pomodoro or break in progress; the task keeps the time worked
+#[tauri::command]
+pub fn pomodoro_stop(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    service: State<'_, Arc<TimerService>>,
+) -> Result<TimerUpdate, NekoError> {
+    service.update(&paths, &tracker, &bases, |timers| {
+        Ok(timers.stop_pomodoro(vault::now_millis()))
+    })
+}
+
+// Settles an idle break: keep, discard or reassign the time away, then
+// resume the paused timers from the user's return
+#[tauri::command]
+pub fn timer_resolve_idle(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    service: State<'_, Arc<TimerService>>,
+    choice: IdleChoice,
+) -> Result<TimerUpdate, NekoError> {
+    if let IdleChoice::Reassign { task_id } = &choice {
+        service.find(&paths, task_id)?;
+    }
+    service.update(&paths, &tracker, &bases, |timers| {
+        Ok(timers.resolve_idle(choice, vault::now_millis()))
+    })
+}
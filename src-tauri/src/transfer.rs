@@ -0,0 +1,162 @@
+// Tasks in and out of the app by drag and drop: a task dropped outside the
+// window is written as a Markdown checklist, and checklist files dropped onto
+// the window become tasks in the active group. Exports go to the outbox
+// inside the vault; the only paths outside it we touch are files the user
+// dropped, which are only read, plus the legacy file `migrate_legacy` reads.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, DragDropEvent, Manager, State, Window, WindowEvent};
+
+use crate::storage::{self, GroupBases};
+use crate::vault::{self, atomic, checklist, DataPaths, ErrorKind, Group, NekoError};
+use crate::watcher::WriteTracker;
+
+const IMPORT_EXTENSIONS: &[&str] = &["md", "markdown", "txt"];
+const MAX_IMPORT_BYTES: u64 = 1024 * 1024;
+
+/// Files last dropped onto the main window. Imports only read these, so the
+/// frontend can't ask the backend to read arbitrary paths.
+#[derive(Default)]
+pub struct DroppedFiles(Mutex<Vec<PathBuf>>);
+
+impl DroppedFiles {
+    fn contains(&self, path: &Path) -> bool {
+        self.0.lock().is_ok_and(|files| files.iter().any(|f| f == path))
+    }
+}
+
+/// Hooked into the builder's `on_window_event` to remember dropped files.
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != "main" {
+        return;
+    }
+    if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+        if let Ok(mut files) = window.state::<DroppedFiles>().0.lock() {
+            files.clone_from(paths);
+        }
+    }
+}
+
+// Whether the cursor is currently outside the main window. Unknown (no
+// cursor position, e.g. on Wayland) counts as inside.
+fn cursor_outside_main(app: &AppHandle) -> bool {
+    let (Some(main), Ok(cursor)) = (app.get_webview_window("main"), app.cursor_position()) else {
+        return false;
+    };
+    let (Ok(pos), Ok(size)) = (main.outer_position(), main.outer_size()) else {
+        return false;
+    };
+    let (left, top) = (pos.x as f64, pos.y as f64);
+    let (right, bottom) = (left + size.width as f64, top + size.height as f64);
+    cursor.x < left || cursor.x >= right || cursor.y < top || cursor.y >= bottom
+}
+
+// `<stem>.md`, or `<stem> (2).md` and so on if that exists already
+fn unique_path(dir: &Path, stem: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.md", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{} ({}).md", stem, n));
+        n += 1;
+    }
+    path
+}
+
+/// Writes the selected tasks and their subtasks as one checklist file in
+/// the outbox and returns its path.
+pub fn export_tasks(paths: &DataPaths, groups: &[Group], task_ids: &[String]) -> Result<PathBuf, NekoError> {
+    let roots = vault::ops::selection_roots(groups, task_ids)?;
+    let Some((first_group, first_id)) = roots.first() else {
+        return Err(NekoError::invalid("nothing to export"));
+    };
+    let stem = groups[*first_group]
+        .tasks
+        .iter()
+        .find(|t| t.id == *first_id)
+        .map(|t| checklist::file_stem(&t.content))
+        .unwrap_or_else(|| "task".to_string());
+
+    let text: String = roots
+        .iter()
+        .map(|(gi, id)| checklist::render_checklist(&groups[*gi], std::slice::from_ref(id)))
+        .collect();
+
+    let dir = vault::settings::load(paths).outbox_dir(paths);
+    paths.ensure_inside(&dir).map_err(|e| NekoError::io(e, &dir))?;
+    fs::create_dir_all(&dir).map_err(|e| NekoError::io(e, &dir))?;
+    let path = unique_path(&dir, &stem);
+    atomic::write_atomic(&path, text.as_bytes()).map_err(|e| NekoError::io(e, &path))?;
+    Ok(path)
+}
+
+// Called when a task drag ends. Returns the exported file if the task was
+// dropped outside the window, `None` for an ordinary drop.
+#[tauri::command]
+pub fn export_drag(
+    app: AppHandle,
+    paths: State<'_, DataPaths>,
+    task_ids: Vec<String>,
+) -> Result<Option<PathBuf>, NekoError> {
+    if !vault::settings::load(&paths).export_on_drop || !cursor_outside_main(&app) {
+        return Ok(None);
+    }
+    let groups = vault::store::load_groups(&paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    export_tasks(&paths, &groups, &task_ids).map(Some)
+}
+
+/// Checkbox lines of a dropped file, if it looks like a checklist at all.
+pub fn read_checklist(path: &Path) -> Result<Vec<checklist::ChecklistItem>, NekoError> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    if !IMPORT_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(NekoError::invalid("not a .md or .txt file").with_path(path));
+    }
+    let meta = fs::metadata(path).map_err(|e| NekoError::io(e, path))?;
+    if meta.len() > MAX_IMPORT_BYTES {
+        return Err(NekoError::invalid("file is too large to import").with_path(path));
+    }
+    let bytes = fs::read(path).map_err(|e| NekoError::io(e, path))?;
+    Ok(checklist::parse_checklist(&String::from_utf8_lossy(&bytes)))
+}
+
+// Imports the checkbox lines of dropped files into a group and returns it
+#[tauri::command]
+pub fn import_files(
+    paths: State<'_, DataPaths>,
+    tracker: State<'_, Arc<WriteTracker>>,
+    bases: State<'_, Arc<GroupBases>>,
+    dropped: State<'_, DroppedFiles>,
+    group_id: String,
+    files: Vec<PathBuf>,
+) -> Result<Group, NekoError> {
+    let mut items = Vec::new();
+    for file in &files {
+        if !dropped.contains(file) {
+            return Err(
+                NekoError::new(ErrorKind::PermissionDenied, "file was not dropped onto the window").with_path(file),
+            );
+        }
+        items.extend(read_checklist(file)?);
+    }
+    if items.is_empty() {
+        return Err(NekoError::invalid("no checkbox lines found"));
+    }
+
+    let changed = storage::edit_groups(&paths, &tracker, &bases, |groups| {
+        let group = groups
+            .iter_mut()
+            .find(|g| g.id == group_id)
+            .ok_or_else(|| NekoError::not_found(format!("no group {:?}", group_id)))?;
+        checklist::import_items(group, &items);
+        Ok(vec![group_id.clone()])
+    })?;
+    changed
+        .into_iter()
+        .next()
+        .ok_or_else(|| NekoError::internal("imported group was not saved"))
+}
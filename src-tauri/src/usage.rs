@@ -0,0 +1,156 @@
+// App-usage collector behind the Time Tracker page. A `FocusSource` names
+// the application in front; on Linux that's the X11 `_NET_ACTIVE_WINDOW`
+// and its process's `/proc/<pid>/comm`. Wayland compositors don't share the
+// focused window, so there only X clients are seen. `Collector` credits the
+// time between samples to the app focused at the earlier one and flushes
+// whole seconds to `time-log.md` every half minute.
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use crate::idle;
+use crate::vault::time_log::{Sampler, UsageKind};
+use crate::vault::{self, DataPaths};
+
+const SAMPLE: Duration = Duration::from_secs(1);
+// Samples between writes to the log
+const FLUSH_EVERY: u64 = 30;
+// A longer gap between samples means the machine slept; it isn't usage
+const MAX_GAP: i64 = 10_000;
+
+pub trait FocusSource {
+    /// The focused application's name, `None` when nothing is focused or it
+    /// can't be told.
+    fn focused_app(&mut self) -> Option<String>;
+}
+
+pub struct Collector {
+    source: Box<dyn FocusSource>,
+    sampler: Sampler,
+}
+
+impl Collector {
+    pub fn new(source: Box<dyn FocusSource>) -> Self {
+        Collector {
+            source,
+            sampler: Sampler::new(MAX_GAP),
+        }
+    }
+
+    /// Credits the time since the last sample to the app focused then and
+    /// notes which one is focused now. While `away` nothing is credited
+    /// after this sample.
+    pub fn sample(&mut self, now: i64, away: bool) {
+        let focused = if away { None } else { self.source.focused_app() };
+        self.sampler.report(now, focused);
+    }
+
+    /// Adds the whole seconds collected so far to `days`.
+    pub fn drain_into(&mut self, days: &mut Vec<vault::DayTimeData>) -> bool {
+        self.sampler.drain_into(days, UsageKind::Apps)
+    }
+
+    pub fn flush(&mut self, paths: &DataPaths) -> io::Result<()> {
+        if self.sampler.is_empty() {
+            return Ok(());
+        }
+        vault::store::update_time_log(paths, |days| self.drain_into(days))
+    }
+}
+
+/// Samples the focused app every second on a thread of its own. Without a
+/// focus source in this session the thread logs why and ends.
+pub fn start(paths: DataPaths) -> io::Result<()> {
+    thread::Builder::new().name("nekotick-usage".into()).spawn(move || {
+        let Some(source) = detect() else {
+            log::warn!("no focused-window source, app usage won't be collected");
+            return;
+        };
+        let mut collector = Collector::new(source);
+        let mut idle = idle::detect();
+        let mut settings = vault::settings::load(&paths);
+        let mut ticks: u64 = 0;
+        loop {
+            thread::sleep(SAMPLE);
+            ticks += 1;
+            if ticks.is_multiple_of(FLUSH_EVERY) {
+                if let Err(e) = collector.flush(&paths) {
+                    log::warn!("failed to write app usage: {}", e);
+                }
+                settings = vault::settings::load(&paths);
+            }
+            // Time at lunch with an editor in front isn't time in the editor
+            let threshold = Duration::from_secs(u64::from(settings.idle.minutes) * 60);
+            let idle_now =
+                settings.idle.minutes > 0 && idle.as_mut().and_then(|s| s.idle_for()).is_some_and(|d| d >= threshold);
+            collector.sample(vault::now_millis(), !settings.track_apps || idle_now);
+        }
+    })?;
+    Ok(())
+}
+
+/// The focus source this session offers, if any.
+pub fn detect() -> Option<Box<dyn FocusSource>> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::X11Focus::open().map(|s| Box::new(s) as Box<dyn FocusSource>)
+    }
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, Window};
+    use x11rb::rust_connection::RustConnection;
+
+    use super::FocusSource;
+
+    // x11rb rather than Xlib: the active window can close between two
+    // requests, and Xlib's default handler would exit on the error
+    pub struct X11Focus {
+        conn: RustConnection,
+        root: Window,
+        active_window: Atom,
+        wm_pid: Atom,
+    }
+
+    impl X11Focus {
+        pub fn open() -> Option<Self> {
+            let (conn, screen) = x11rb::connect(None).ok()?;
+            let root = conn.setup().roots.get(screen)?.root;
+            let active_window = conn.intern_atom(false, b"_NET_ACTIVE_WINDOW").ok()?.reply().ok()?.atom;
+            let wm_pid = conn.intern_atom(false, b"_NET_WM_PID").ok()?.reply().ok()?.atom;
+            Some(X11Focus {
+                conn,
+                root,
+                active_window,
+                wm_pid,
+            })
+        }
+
+        // The first 32-bit value of a window property
+        fn cardinal(&self, window: Window, property: Atom) -> Option<u32> {
+            let reply = self
+                .conn
+                .get_property(false, window, property, AtomEnum::ANY, 0, 1)
+                .ok()?
+                .reply()
+                .ok()?;
+            let value = reply.value32()?.next();
+            value
+        }
+    }
+
+    impl FocusSource for X11Focus {
+        fn focused_app(&mut self) -> Option<String> {
+            let window = self.cardinal(self.root, self.active_window).filter(|w| *w != 0)?;
+            let pid = self.cardinal(window, self.wm_pid)?;
+            let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+            Some(comm.trim().to_string()).filter(|name| !name.is_empty())
+        }
+    }
+}
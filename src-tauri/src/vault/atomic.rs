@@ -0,0 +1,142 @@
+// Crash-safe file replacement with rolling `.bak` generations
+//
+// A write goes to a hidden temp file in the target's folder, is fsynced,
+// renamed over the target, and then the folder itself is fsynced so the
+// rename survives power loss. Before replacing a file that still looks
+// healthy we keep it as `<name>.1.bak`, shifting older generations up.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How many previous versions of each file are kept.
+pub const BACKUP_GENERATIONS: usize = 3;
+
+/// `tasks/inbox.md` -> `tasks/inbox.md.<generation>.bak`
+pub fn backup_path(path: &Path, generation: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.bak", generation));
+    path.with_file_name(name)
+}
+
+// Unique per write: two threads saving the same file must not truncate
+// each other's temp file
+fn temp_path(path: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
+/// True for the hidden temp files `write_atomic` leaves behind mid-write.
+pub fn is_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.') && n.ends_with(".tmp"))
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+// Directories can't be opened for syncing on Windows; rename is durable enough there
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Replaces `path` with `contents` so readers only ever see the old or the new file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let tmp = temp_path(path);
+
+    let result = (|| {
+        let mut file = OpenOptions::new().write(true).create(true).truncate(true).open(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result?;
+
+    sync_dir(dir)
+}
+
+// Shift `.1.bak` -> `.2.bak` ... and keep the current file as `.1.bak`
+fn rotate_backups(path: &Path, generations: usize) -> io::Result<()> {
+    let _ = fs::remove_file(backup_path(path, generations));
+    for generation in (1..generations).rev() {
+        let from = backup_path(path, generation);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, generation + 1))?;
+        }
+    }
+    // Copy rather than hard-link: editors that save in place would otherwise
+    // rewrite the backup along with the file
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Atomically writes `contents`, first keeping the current file as a backup
+/// if `is_good` accepts it. Damaged files are never rotated into the backups.
+pub fn write_with_backups(
+    path: &Path,
+    contents: &[u8],
+    generations: usize,
+    is_good: impl Fn(&[u8]) -> bool,
+) -> io::Result<()> {
+    if generations > 0 {
+        if let Ok(current) = fs::read(path) {
+            if is_good(&current) && current != contents {
+                rotate_backups(path, generations)?;
+            }
+        }
+    }
+    write_atomic(path, contents)
+}
+
+/// Where a loaded file actually came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Primary,
+    Backup(usize),
+}
+
+/// Reads `path`, falling back to the newest backup `is_good` accepts. If
+/// nothing passes, the primary file is returned as-is so the caller can
+/// still salvage what it can.
+pub fn read_with_fallback(
+    path: &Path,
+    generations: usize,
+    is_good: impl Fn(&[u8]) -> bool,
+) -> io::Result<(Vec<u8>, Source)> {
+    let primary = fs::read(path);
+    if let Ok(bytes) = &primary {
+        if is_good(bytes) {
+            return Ok((bytes.clone(), Source::Primary));
+        }
+    }
+
+    for generation in 1..=generations {
+        if let Ok(bytes) = fs::read(backup_path(path, generation)) {
+            if is_good(&bytes) {
+                return Ok((bytes, Source::Backup(generation)));
+            }
+        }
+    }
+
+    primary.map(|bytes| (bytes, Source::Primary))
+}
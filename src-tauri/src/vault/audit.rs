@@ -0,0 +1,101 @@
+// Estimate-vs-actual totals for the time audit. Only tasks with an estimate
+// or some actual time count. Tags are the `#words` in a task's text.
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::tasks::{Group, Task};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Totals {
+    pub tasks: usize,
+    pub completed: usize,
+    pub estimated_minutes: u64,
+    pub actual_minutes: u64,
+}
+
+impl Totals {
+    fn add(&mut self, task: &Task) {
+        self.tasks += 1;
+        self.completed += usize::from(task.completed);
+        self.estimated_minutes += u64::from(task.estimated_minutes.unwrap_or(0));
+        // Rounded per task so a group's total equals the sum of what its rows show
+        self.actual_minutes += ((task.actual_millis() + 30_000) / 60_000) as u64;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupTotals {
+    pub group_id: String,
+    pub name: String,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagTotals {
+    pub tag: String,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub total: Totals,
+    /// In vault order, groups without audited tasks left out.
+    pub groups: Vec<GroupTotals>,
+    /// Sorted by tag; a task counts once under each of its tags.
+    pub tags: Vec<TagTotals>,
+}
+
+/// `#tags` in task text, without the `#`, each once. A lone `#` or a
+/// Markdown heading marker is not a tag.
+pub fn tags(content: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for word in content.split_whitespace() {
+        let Some(rest) = word.strip_prefix('#') else {
+            continue;
+        };
+        let tag: String = rest
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == '/')
+            .collect();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn audited(task: &Task) -> bool {
+    task.estimated_minutes.is_some() || task.actual_millis() > 0
+}
+
+pub fn summarize(groups: &[Group]) -> Summary {
+    let mut summary = Summary::default();
+    let mut by_tag: BTreeMap<String, Totals> = BTreeMap::new();
+    for group in groups {
+        let mut totals = Totals::default();
+        for task in group.tasks.iter().filter(|t| audited(t)) {
+            totals.add(task);
+            summary.total.add(task);
+            for tag in tags(&task.content) {
+                by_tag.entry(tag).or_default().add(task);
+            }
+        }
+        if totals.tasks > 0 {
+            summary.groups.push(GroupTotals {
+                group_id: group.id.clone(),
+                name: group.name.clone(),
+                totals,
+            });
+        }
+    }
+    summary.tags = by_tag
+        .into_iter()
+        .map(|(tag, totals)| TagTotals { tag, totals })
+        .collect();
+    summary
+}
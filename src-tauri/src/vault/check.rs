@@ -0,0 +1,233 @@
+// Structural problems in loaded groups that the UI can't show and the next
+// save would lose: duplicate ids, parents that don't exist, `parent:` cycles
+// and sibling orders outside 0..n. `check` only reports; `repair` applies the
+// same fixes in memory and leaves writing to the caller.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::ops;
+use super::tasks::Group;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Issue {
+    /// A second task with an id already used in the vault; it gets `new_id`.
+    DuplicateId {
+        group_id: String,
+        task_id: String,
+        new_id: String,
+    },
+    /// `parent:` names a task that isn't in the group; moved to the top level.
+    DanglingParent {
+        group_id: String,
+        task_id: String,
+        parent_id: String,
+    },
+    /// Tasks that are each other's ancestors; `cut` is moved to the top level.
+    ParentCycle {
+        group_id: String,
+        task_ids: Vec<String>,
+        cut: String,
+    },
+    /// Siblings whose orders aren't 0..n; renumbered keeping their sequence.
+    OrderOutOfRange {
+        group_id: String,
+        parent_id: Option<String>,
+        orders: Vec<i64>,
+    },
+}
+
+impl Issue {
+    pub fn group_id(&self) -> &str {
+        match self {
+            Issue::DuplicateId { group_id, .. }
+            | Issue::DanglingParent { group_id, .. }
+            | Issue::ParentCycle { group_id, .. }
+            | Issue::OrderOutOfRange { group_id, .. } => group_id,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::DuplicateId {
+                group_id,
+                task_id,
+                new_id,
+            } => {
+                write!(f, "{}: duplicate id {}, renamed to {}", group_id, task_id, new_id)
+            }
+            Issue::DanglingParent {
+                group_id,
+                task_id,
+                parent_id,
+            } => write!(
+                f,
+                "{}: {} has missing parent {}, moved to the top level",
+                group_id, task_id, parent_id
+            ),
+            Issue::ParentCycle {
+                group_id,
+                task_ids,
+                cut,
+            } => write!(
+                f,
+                "{}: parent cycle {}, {} moved to the top level",
+                group_id,
+                task_ids.join(" -> "),
+                cut
+            ),
+            Issue::OrderOutOfRange {
+                group_id,
+                parent_id,
+                orders,
+            } => write!(
+                f,
+                "{}: children of {} have orders {:?}, renumbered",
+                group_id,
+                parent_id.as_deref().unwrap_or("the top level"),
+                orders
+            ),
+        }
+    }
+}
+
+// Ids are unique across the vault: the frontend keeps all tasks in one list.
+// Later copies are renamed; each group's first renamed copy takes that
+// group's children along.
+fn fix_duplicate_ids(groups: &mut [Group], issues: &mut Vec<Issue>) {
+    let mut taken: HashSet<String> = groups
+        .iter()
+        .flat_map(|g| g.tasks.iter().map(|t| t.id.clone()))
+        .collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    for group in groups.iter_mut() {
+        let mut seen_here: HashSet<String> = HashSet::new();
+        let mut adopted: HashMap<String, String> = HashMap::new();
+        for task in group.tasks.iter_mut() {
+            let first_here = seen_here.insert(task.id.clone());
+            if seen.insert(task.id.clone()) {
+                continue;
+            }
+            let new_id = (2..)
+                .map(|n| format!("{}-{}", task.id, n))
+                .find(|id| !taken.contains(id))
+                .unwrap_or_default();
+            taken.insert(new_id.clone());
+            seen.insert(new_id.clone());
+            issues.push(Issue::DuplicateId {
+                group_id: group.id.clone(),
+                task_id: task.id.clone(),
+                new_id: new_id.clone(),
+            });
+            if first_here {
+                adopted.insert(task.id.clone(), new_id.clone());
+            }
+            task.id = new_id;
+        }
+        for task in group.tasks.iter_mut() {
+            if let Some(new_parent) = task.parent_id.as_ref().and_then(|p| adopted.get(p)) {
+                task.parent_id = Some(new_parent.clone());
+            }
+        }
+    }
+}
+
+fn fix_dangling_parents(group: &mut Group, issues: &mut Vec<Issue>) {
+    let ids: HashSet<String> = group.tasks.iter().map(|t| t.id.clone()).collect();
+    for task in group.tasks.iter_mut() {
+        let Some(parent) = task.parent_id.clone() else {
+            continue;
+        };
+        if !ids.contains(&parent) {
+            issues.push(Issue::DanglingParent {
+                group_id: group.id.clone(),
+                task_id: task.id.clone(),
+                parent_id: parent,
+            });
+            task.parent_id = None;
+        }
+    }
+}
+
+// Runs after the other fixes, so ids are unique and every parent exists
+fn fix_cycles(group: &mut Group, issues: &mut Vec<Issue>) {
+    let index: HashMap<String, usize> = group.tasks.iter().enumerate().map(|(i, t)| (t.id.clone(), i)).collect();
+    let mut done = vec![false; group.tasks.len()];
+    for start in 0..group.tasks.len() {
+        let mut path: Vec<usize> = Vec::new();
+        let mut current = Some(start);
+        while let Some(i) = current {
+            if done[i] {
+                break;
+            }
+            if let Some(pos) = path.iter().position(|&p| p == i) {
+                let members = &path[pos..];
+                // Cut at the member that comes first in the file
+                let cut = *members.iter().min().unwrap_or(&i);
+                issues.push(Issue::ParentCycle {
+                    group_id: group.id.clone(),
+                    task_ids: members.iter().map(|&m| group.tasks[m].id.clone()).collect(),
+                    cut: group.tasks[cut].id.clone(),
+                });
+                group.tasks[cut].parent_id = None;
+                break;
+            }
+            path.push(i);
+            current = group.tasks[i].parent_id.as_ref().and_then(|p| index.get(p).copied());
+        }
+        for i in path {
+            done[i] = true;
+        }
+    }
+}
+
+fn fix_orders(group: &mut Group, issues: &mut Vec<Issue>) {
+    let mut parents: Vec<Option<String>> = Vec::new();
+    for task in &group.tasks {
+        if !parents.contains(&task.parent_id) {
+            parents.push(task.parent_id.clone());
+        }
+    }
+    for parent in parents {
+        let orders: Vec<i64> = group
+            .tasks
+            .iter()
+            .filter(|t| t.parent_id == parent)
+            .map(|t| t.order)
+            .collect();
+        let mut sorted = orders.clone();
+        sorted.sort_unstable();
+        if sorted.iter().enumerate().all(|(i, &o)| o == i as i64) {
+            continue;
+        }
+        issues.push(Issue::OrderOutOfRange {
+            group_id: group.id.clone(),
+            parent_id: parent.clone(),
+            orders,
+        });
+        ops::renumber(group, parent.as_deref());
+    }
+}
+
+/// Fixes every issue in place and returns what was fixed. The result only
+/// depends on the input, so a report from `check` describes exactly what
+/// `repair` will do.
+pub fn repair(groups: &mut [Group]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    fix_duplicate_ids(groups, &mut issues);
+    for group in groups.iter_mut() {
+        fix_dangling_parents(group, &mut issues);
+        fix_cycles(group, &mut issues);
+        fix_orders(group, &mut issues);
+    }
+    issues
+}
+
+/// The issues `repair` would fix, without touching `groups`.
+pub fn check(groups: &[Group]) -> Vec<Issue> {
+    repair(&mut groups.to_vec())
+}
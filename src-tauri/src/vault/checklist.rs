@@ -0,0 +1,109 @@
+// Plain Markdown checklists for swapping tasks with other apps: no metadata
+// comments, hierarchy expressed only by indentation.
+use super::now_millis;
+use super::ops;
+use super::tasks::{indent_width, parse_checkbox, split_meta, Group, Task};
+
+/// A checkbox line read from a foreign file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub depth: usize,
+    pub completed: bool,
+    pub content: String,
+}
+
+/// Renders the given tasks and their subtasks as an indented checklist.
+pub fn render_checklist(group: &Group, roots: &[String]) -> String {
+    fn walk(group: &Group, task: &Task, depth: usize, out: &mut String) {
+        let mark = if task.completed { "x" } else { " " };
+        out.push_str(&format!("{}- [{}] {}\n", "  ".repeat(depth), mark, task.content));
+        let mut children: Vec<&Task> = group
+            .tasks
+            .iter()
+            .filter(|t| t.parent_id.as_deref() == Some(task.id.as_str()))
+            .collect();
+        children.sort_by_key(|t| t.order);
+        for child in children {
+            // Guard against `parent:` cycles in hand-edited files
+            if depth < 64 {
+                walk(group, child, depth + 1, out);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for id in roots {
+        if let Some(task) = group.tasks.iter().find(|t| t.id == *id) {
+            walk(group, task, 0, &mut out);
+        }
+    }
+    out
+}
+
+/// Reads every checkbox line of `text`. Depth comes from indentation
+/// relative to the enclosing items, whatever the indent width.
+pub fn parse_checklist(text: &str) -> Vec<ChecklistItem> {
+    let mut items = Vec::new();
+    // Indent widths of the currently open ancestors
+    let mut open: Vec<usize> = Vec::new();
+    for line in text.lines() {
+        let Some((completed, body)) = parse_checkbox(line.trim()) else {
+            continue;
+        };
+        // Files exported from a vault still carry their metadata
+        let (content, _) = split_meta(body);
+        if content.is_empty() {
+            continue;
+        }
+        let indent = indent_width(line);
+        while open.last().is_some_and(|&w| w >= indent) {
+            open.pop();
+        }
+        items.push(ChecklistItem {
+            depth: open.len(),
+            completed,
+            content: content.to_string(),
+        });
+        open.push(indent);
+    }
+    items
+}
+
+/// Appends checklist items to `group`, nesting them by depth. Returns the
+/// new task ids.
+pub fn import_items(group: &mut Group, items: &[ChecklistItem]) -> Vec<String> {
+    let mut ids = Vec::new();
+    // Id of the last task added at each depth
+    let mut parents: Vec<String> = Vec::new();
+    for item in items {
+        parents.truncate(item.depth);
+        let Ok(id) = ops::add_task(group, &item.content, parents.last().map(String::as_str), None) else {
+            continue;
+        };
+        if item.completed {
+            if let Some(task) = group.tasks.iter_mut().find(|t| t.id == id) {
+                task.completed = true;
+                task.completed_at = Some(now_millis());
+            }
+        }
+        parents.push(id.clone());
+        ids.push(id);
+    }
+    ids
+}
+
+/// A file name for exported text: path separators and characters Windows
+/// rejects are dropped and the length is capped.
+pub fn file_stem(content: &str) -> String {
+    let cleaned: String = content
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .take(60)
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    if cleaned.is_empty() {
+        "task".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
@@ -0,0 +1,132 @@
+// Error type shared by the vault and every backend command. It reaches the
+// frontend as `{ kind, message, path?, taskId? }`; `kind` is stable so the UI
+// can map it to a localized message, `message` is English detail for logs.
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Bad arguments: empty text, malformed ids, unsupported files.
+    InvalidInput,
+    /// A group, task or file that doesn't exist.
+    NotFound,
+    /// The OS refused access, or a path lies outside what we may touch.
+    PermissionDenied,
+    /// Any other filesystem failure.
+    Io,
+    /// A window we needed is gone or couldn't be created.
+    WindowUnavailable,
+    /// A bug or poisoned lock; nothing the user can fix.
+    Internal,
+}
+
+impl ErrorKind {
+    /// The stable code sent to the frontend.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::PermissionDenied => "permission_denied",
+            ErrorKind::Io => "io",
+            ErrorKind::WindowUnavailable => "window_unavailable",
+            ErrorKind::Internal => "internal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NekoError {
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+}
+
+pub type Result<T, E = NekoError> = std::result::Result<T, E>;
+
+impl NekoError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        NekoError {
+            kind,
+            message: message.into(),
+            path: None,
+            task_id: None,
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    /// An I/O error on `path`.
+    pub fn io(err: io::Error, path: &Path) -> Self {
+        NekoError::from(err).with_path(path)
+    }
+
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_task(mut self, task_id: impl Into<String>) -> Self {
+        self.task_id = Some(task_id.into());
+        self
+    }
+}
+
+impl fmt::Display for NekoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}: {}", path.display(), self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for NekoError {}
+
+impl From<io::Error> for NekoError {
+    fn from(err: io::Error) -> Self {
+        let kind = match err.kind() {
+            io::ErrorKind::NotFound => ErrorKind::NotFound,
+            io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData => ErrorKind::InvalidInput,
+            _ => ErrorKind::Io,
+        };
+        NekoError::new(kind, err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for NekoError {
+    fn from(err: serde_json::Error) -> Self {
+        NekoError::invalid(err.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for NekoError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        NekoError::internal("a background task panicked while holding shared state")
+    }
+}
+
+// Lets the CLI, which reports plain strings, use `?` on vault calls
+impl From<NekoError> for String {
+    fn from(err: NekoError) -> Self {
+        err.to_string()
+    }
+}
@@ -0,0 +1,230 @@
+// Clean Markdown mode: group files hold bare `- [ ] text` lines and the
+// metadata a bare line can't carry lives in `.nekotick/index.json`, one
+// entry per task in file order. Order and nesting are the lines' positions,
+// except for a parent the indentation can't show, which the entry keeps.
+//
+// Files edited by hand are matched back to their entries: first by content
+// hash, taking the entry nearest to the line's position, then by neighbours
+// so a retyped line keeps its id: it takes the unused entry right after the
+// previous line's, or right before the next line's. Lines left over become
+// new tasks.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use super::atomic;
+use super::paths::DataPaths;
+use super::tasks::{self, Group, Interval, Priority};
+
+pub const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub id: String,
+    /// `content_hash` of the task text when it was written.
+    pub hash: String,
+    pub created_at: i64,
+    /// Only for a task written at the top level that has a parent anyway,
+    /// because the parent is missing or stuck in a cycle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_time: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapsed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<Interval>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VaultIndex {
+    pub version: u32,
+    /// Entries per group id, in the order the group file lists its tasks.
+    pub groups: BTreeMap<String, Vec<IndexEntry>>,
+}
+
+impl Default for VaultIndex {
+    fn default() -> Self {
+        VaultIndex {
+            version: INDEX_VERSION,
+            groups: BTreeMap::new(),
+        }
+    }
+}
+
+/// FNV-1a of the trimmed text: stable across Rust versions, unlike `Hash`.
+pub fn content_hash(text: &str) -> String {
+    let hash = text.trim().bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// The entries for `group` as `serialize_group_clean` writes it.
+pub fn entries(group: &Group) -> Vec<IndexEntry> {
+    tasks::layout(group)
+        .into_iter()
+        .map(|(task, depth)| IndexEntry {
+            id: task.id.clone(),
+            hash: content_hash(&task.content),
+            created_at: task.created_at,
+            parent_id: task.parent_id.clone().filter(|_| depth == 0),
+            completed_at: task.completed_at,
+            scheduled_time: task.scheduled_time.clone(),
+            collapsed: task.collapsed,
+            priority: task.priority,
+            estimated_minutes: task.estimated_minutes,
+            actual_minutes: task.actual_minutes,
+            intervals: task.intervals.clone(),
+            extra: task.extra.clone(),
+        })
+        .collect()
+}
+
+/// Parses a group file, giving lines without an `id:` the id and metadata
+/// of their index entry. Lines with metadata are left as they are, so files
+/// in either format, or a mix, load the same way.
+pub fn parse_group(id: &str, content: &str, entries: &[IndexEntry]) -> Group {
+    let (mut group, had_id) = tasks::parse_group_lines(id, content);
+    if entries.is_empty() || had_id.iter().all(|&h| h) {
+        return group;
+    }
+
+    // An entry whose id is already on some line can't be used again
+    let taken: HashSet<&str> = group
+        .tasks
+        .iter()
+        .zip(&had_id)
+        .filter(|(_, &h)| h)
+        .map(|(t, _)| t.id.as_str())
+        .collect();
+    let mut used: Vec<bool> = entries.iter().map(|e| taken.contains(e.id.as_str())).collect();
+    let mut matched: Vec<Option<usize>> = vec![None; group.tasks.len()];
+
+    for (pos, task) in group.tasks.iter().enumerate() {
+        if had_id[pos] {
+            continue;
+        }
+        let hash = content_hash(&task.content);
+        let best = (0..entries.len())
+            .filter(|&e| !used[e] && entries[e].hash == hash)
+            .min_by_key(|&e| e.abs_diff(pos));
+        if let Some(e) = best {
+            used[e] = true;
+            matched[pos] = Some(e);
+        }
+    }
+    // Lines that kept their id anchor their neighbours as well
+    let by_id: HashMap<&str, usize> = entries
+        .iter()
+        .enumerate()
+        .map(|(e, entry)| (entry.id.as_str(), e))
+        .collect();
+    let mut anchor: Vec<Option<usize>> = (0..group.tasks.len())
+        .map(|pos| {
+            if had_id[pos] {
+                by_id.get(group.tasks[pos].id.as_str()).copied()
+            } else {
+                matched[pos]
+            }
+        })
+        .collect();
+    for pos in 0..group.tasks.len() {
+        if had_id[pos] || matched[pos].is_some() {
+            continue;
+        }
+        let after_previous = match anchor[..pos].iter().rev().flatten().next() {
+            Some(&e) => e + 1,
+            None => 0,
+        };
+        let before_next = anchor[pos + 1..]
+            .iter()
+            .flatten()
+            .next()
+            .copied()
+            .unwrap_or(entries.len());
+        let pick = [Some(after_previous), before_next.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .find(|&e| e < entries.len() && !used[e]);
+        if let Some(e) = pick {
+            used[e] = true;
+            matched[pos] = Some(e);
+            anchor[pos] = Some(e);
+        }
+    }
+
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    for (task, entry) in group.tasks.iter_mut().zip(&matched) {
+        let Some(entry) = entry.map(|e| &entries[e]) else {
+            continue;
+        };
+        renamed.insert(task.id.clone(), entry.id.clone());
+        task.id = entry.id.clone();
+        task.created_at = entry.created_at;
+        // The checkbox on the line wins over what the index remembers
+        task.completed_at = entry.completed_at.filter(|_| task.completed);
+        task.scheduled_time = entry.scheduled_time.clone();
+        task.collapsed = entry.collapsed;
+        task.priority = entry.priority;
+        task.estimated_minutes = entry.estimated_minutes;
+        task.actual_minutes = entry.actual_minutes;
+        task.intervals = entry.intervals.clone();
+        task.extra = entry.extra.clone();
+    }
+    // Parents inferred from indentation still point at generated ids, and
+    // so do the notes that followed those lines
+    for task in group.tasks.iter_mut() {
+        if let Some(new_id) = task.parent_id.as_ref().and_then(|p| renamed.get(p)) {
+            task.parent_id = Some(new_id.clone());
+        }
+    }
+    for note in group.extra.iter_mut() {
+        if let Some(new_id) = note.after.as_ref().and_then(|a| renamed.get(a)) {
+            note.after = Some(new_id.clone());
+        }
+    }
+    // A line still at the top level gets back the parent its indent couldn't show
+    for (task, entry) in group.tasks.iter_mut().zip(&matched) {
+        if let (None, Some(e)) = (&task.parent_id, entry) {
+            task.parent_id = entries[*e].parent_id.clone();
+        }
+    }
+    group
+}
+
+/// A missing or unreadable index is empty: every bare line is a new task.
+pub fn load(paths: &DataPaths) -> VaultIndex {
+    let path = paths.index_file();
+    if paths.ensure_inside(&path).is_err() {
+        return VaultIndex::default();
+    }
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(paths: &DataPaths, index: &VaultIndex) -> io::Result<()> {
+    let path = paths.index_file();
+    paths.ensure_inside(&path)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_vec_pretty(index)?;
+    atomic::write_atomic(&path, &json)
+}
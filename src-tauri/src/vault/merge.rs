@@ -0,0 +1,246 @@
+// Three-way merge of a group file
+//
+// `base` is the version the app last loaded, `ours` is what the app wants to
+// save and `theirs` is what is on disk now (an editor or Syncthing changed it
+// underneath us). Tasks are matched by their `id:` metadata and merged field
+// by field: a side that left a field untouched takes the other side's change.
+// When both sides changed the same field differently ours wins and a copy of
+// their version is inserted right after it as a conflict marker task.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+
+use serde::Serialize;
+
+use super::tasks::{Group, Interval, Task};
+
+pub const CONFLICT_KEY: &str = "conflict";
+pub const CONFLICT_PREFIX: &str = "[冲突] ";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conflict {
+    /// `None` for group-level fields such as the name.
+    pub task_id: Option<String>,
+    pub field: &'static str,
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOutcome {
+    pub group: Group,
+    pub conflicts: Vec<Conflict>,
+}
+
+// Ok(value) if the sides can be reconciled, Err(()) on a real conflict
+fn pick<T: PartialEq + Clone>(base: Option<&T>, ours: &T, theirs: &T) -> Result<T, ()> {
+    if ours == theirs || base == Some(theirs) {
+        Ok(ours.clone())
+    } else if base == Some(ours) {
+        Ok(theirs.clone())
+    } else {
+        Err(())
+    }
+}
+
+struct FieldMerger<'a> {
+    task_id: Option<&'a str>,
+    conflicts: Vec<Conflict>,
+}
+
+impl FieldMerger<'_> {
+    fn field<T: PartialEq + Clone + Debug>(&mut self, name: &'static str, base: Option<&T>, ours: &T, theirs: &T) -> T {
+        pick(base, ours, theirs).unwrap_or_else(|()| {
+            self.conflicts.push(Conflict {
+                task_id: self.task_id.map(str::to_string),
+                field: name,
+                ours: format!("{:?}", ours),
+                theirs: format!("{:?}", theirs),
+            });
+            ours.clone()
+        })
+    }
+}
+
+fn merge_task(base: Option<&Task>, ours: &Task, theirs: &Task) -> (Task, Vec<Conflict>) {
+    let mut m = FieldMerger {
+        task_id: Some(&ours.id),
+        conflicts: Vec::new(),
+    };
+
+    let content = m.field("content", base.map(|b| &b.content), &ours.content, &theirs.content);
+    // The checkbox and its timestamp move together
+    let (completed, completed_at) = m.field(
+        "completed",
+        base.map(|b| (b.completed, b.completed_at)).as_ref(),
+        &(ours.completed, ours.completed_at),
+        &(theirs.completed, theirs.completed_at),
+    );
+    let order = m.field("order", base.map(|b| &b.order), &ours.order, &theirs.order);
+    let priority = m.field("priority", base.map(|b| &b.priority), &ours.priority, &theirs.priority);
+    let parent_id = m.field("parent", base.map(|b| &b.parent_id), &ours.parent_id, &theirs.parent_id);
+    let collapsed = m.field(
+        "collapsed",
+        base.map(|b| &b.collapsed),
+        &ours.collapsed,
+        &theirs.collapsed,
+    );
+    let scheduled_time = m.field(
+        "time",
+        base.map(|b| &b.scheduled_time),
+        &ours.scheduled_time,
+        &theirs.scheduled_time,
+    );
+    let estimated_minutes = m.field(
+        "est",
+        base.map(|b| &b.estimated_minutes),
+        &ours.estimated_minutes,
+        &theirs.estimated_minutes,
+    );
+    let actual_minutes = m.field(
+        "act",
+        base.map(|b| &b.actual_minutes),
+        &ours.actual_minutes,
+        &theirs.actual_minutes,
+    );
+    // Sessions are only ever added, so keep every one either side logged
+    let mut intervals: Vec<Interval> = ours.intervals.iter().chain(&theirs.intervals).copied().collect();
+    intervals.sort();
+    intervals.dedup();
+
+    let task = Task {
+        id: ours.id.clone(),
+        content,
+        completed,
+        created_at: ours.created_at.min(theirs.created_at),
+        completed_at,
+        scheduled_time,
+        order,
+        parent_id,
+        collapsed,
+        priority,
+        estimated_minutes,
+        actual_minutes,
+        intervals,
+        // The app never edits unknown metadata, so the file's copy is authoritative
+        extra: if theirs.extra.is_empty() {
+            ours.extra.clone()
+        } else {
+            theirs.extra.clone()
+        },
+    };
+    (task, m.conflicts)
+}
+
+// Equal apart from unknown metadata, which the app doesn't round-trip
+fn same_task(a: &Task, b: &Task) -> bool {
+    Task {
+        extra: Vec::new(),
+        ..a.clone()
+    } == Task {
+        extra: Vec::new(),
+        ..b.clone()
+    }
+}
+
+fn conflict_marker(merged: &Task, theirs: &Task, seq: usize) -> Task {
+    let mut marker = theirs.clone();
+    marker.id = format!("{}-conflict-{}", theirs.id, seq);
+    marker.content = format!("{}{}", CONFLICT_PREFIX, theirs.content);
+    marker.parent_id = merged.parent_id.clone();
+    marker.order = merged.order;
+    // The time stays on the task itself; a copy would count it twice
+    marker.estimated_minutes = None;
+    marker.actual_minutes = None;
+    marker.intervals.clear();
+    marker.extra.retain(|(k, _)| k != CONFLICT_KEY);
+    marker.extra.push((CONFLICT_KEY.to_string(), theirs.id.clone()));
+    marker
+}
+
+/// Merges `ours` and `theirs` against their common ancestor `base`.
+pub fn merge_groups(base: &Group, ours: &Group, theirs: &Group) -> MergeOutcome {
+    let mut conflicts = Vec::new();
+
+    let mut m = FieldMerger {
+        task_id: None,
+        conflicts: Vec::new(),
+    };
+    let name = m.field("name", Some(&base.name), &ours.name, &theirs.name);
+    let pinned = m.field("pinned", Some(&base.pinned), &ours.pinned, &theirs.pinned);
+    conflicts.append(&mut m.conflicts);
+
+    let base_tasks: HashMap<&str, &Task> = base.tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let their_tasks: HashMap<&str, &Task> = theirs.tasks.iter().map(|t| (t.id.as_str(), t)).collect();
+    let our_ids: HashSet<&str> = ours.tasks.iter().map(|t| t.id.as_str()).collect();
+    let existing_ids: HashSet<&str> = our_ids.iter().chain(their_tasks.keys()).copied().collect();
+
+    let mut tasks = Vec::new();
+    let mut seq = 0;
+
+    // Our order first, their additions after
+    for ours_task in &ours.tasks {
+        let id = ours_task.id.as_str();
+        let base_task = base_tasks.get(id).copied();
+        match (base_task, their_tasks.get(id).copied()) {
+            (_, Some(theirs_task)) => {
+                let (merged, task_conflicts) = merge_task(base_task, ours_task, theirs_task);
+                let marker = if task_conflicts.is_empty() {
+                    None
+                } else {
+                    // Pick a marker id nobody uses yet
+                    let mut marker = conflict_marker(&merged, theirs_task, seq);
+                    while existing_ids.contains(marker.id.as_str()) {
+                        seq += 1;
+                        marker = conflict_marker(&merged, theirs_task, seq);
+                    }
+                    seq += 1;
+                    Some(marker)
+                };
+                conflicts.extend(task_conflicts);
+                tasks.push(merged);
+                tasks.extend(marker);
+            }
+            // They deleted it; keep it only if we changed it since
+            (Some(base_task), None) => {
+                if !same_task(base_task, ours_task) {
+                    tasks.push(ours_task.clone());
+                }
+            }
+            // We added it
+            (None, None) => tasks.push(ours_task.clone()),
+        }
+    }
+
+    for theirs_task in &theirs.tasks {
+        let id = theirs_task.id.as_str();
+        if our_ids.contains(id) {
+            continue;
+        }
+        match base_tasks.get(id) {
+            // We deleted it; keep it only if they changed it since
+            Some(base_task) if same_task(base_task, theirs_task) => {}
+            _ => tasks.push(theirs_task.clone()),
+        }
+    }
+
+    MergeOutcome {
+        group: Group {
+            id: ours.id.clone(),
+            name,
+            pinned,
+            tasks,
+            created_at: ours.created_at.min(theirs.created_at),
+            updated_at: ours.updated_at.max(theirs.updated_at),
+            extra: if theirs.extra.is_empty() {
+                ours.extra.clone()
+            } else {
+                theirs.extra.clone()
+            },
+            line_ending: theirs.line_ending,
+            final_newline: theirs.final_newline,
+        },
+        conflicts,
+    }
+}
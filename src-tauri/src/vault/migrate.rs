@@ -0,0 +1,74 @@
+// One-shot import of the single-file format early builds kept in
+// `Documents/NekoTick/nekotick.md`: a flat task list with `{est:N}` style
+// tags. Its tasks become a new group; the old file is only read, never
+// changed, and tasks whose id the vault already has are skipped, so running
+// the migration again imports nothing twice.
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::ops;
+use super::paths::DIR_NAME;
+use super::tasks::{self, Group};
+
+pub const LEGACY_FILE: &str = "nekotick.md";
+
+/// Where early builds kept their tasks, if the platform has a documents folder.
+pub fn legacy_file() -> Option<PathBuf> {
+    dirs::document_dir().map(|dir| dir.join(DIR_NAME).join(LEGACY_FILE))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Migration {
+    /// The new group, `None` when every task was already in the vault.
+    pub group: Option<Group>,
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+/// Turns the legacy file's content into a group for `groups`, picking an id
+/// none of them uses.
+pub fn migrate(groups: &[Group], content: &str) -> Migration {
+    let base = "nekotick-md";
+    let id = (1..)
+        .map(|n| {
+            if n == 1 {
+                base.to_string()
+            } else {
+                format!("{}-{}", base, n)
+            }
+        })
+        .find(|id| groups.iter().all(|g| &g.id != id))
+        .unwrap_or_default();
+
+    let mut group = tasks::parse_group(&id, content);
+    if group.name == "未命名" {
+        group.name = LEGACY_FILE.to_string();
+    }
+    let before = group.tasks.len();
+    group
+        .tasks
+        .retain(|t| !groups.iter().any(|g| g.tasks.iter().any(|other| other.id == t.id)));
+    let imported = group.tasks.len();
+    // Parents that stayed behind are taken to the top level
+    let kept: Vec<String> = group.tasks.iter().map(|t| t.id.clone()).collect();
+    for task in group.tasks.iter_mut() {
+        if task.parent_id.as_ref().is_some_and(|p| !kept.contains(p)) {
+            task.parent_id = None;
+        }
+    }
+    // Skipped tasks leave gaps in the orders
+    let mut parents: Vec<Option<String>> = group.tasks.iter().map(|t| t.parent_id.clone()).collect();
+    parents.sort();
+    parents.dedup();
+    for parent in parents {
+        ops::renumber(&mut group, parent.as_deref());
+    }
+
+    Migration {
+        group: (imported > 0).then_some(group),
+        imported,
+        skipped: before - imported,
+    }
+}
@@ -0,0 +1,69 @@
+// Vault core: typed models and the on-disk Markdown formats.
+//
+// Everything in here is plain Rust with no Tauri dependency, so the file
+// formats can be exercised from `cargo test` and shared with other binaries.
+
+pub mod atomic;
+pub mod audit;
+pub mod check;
+pub mod checklist;
+pub mod error;
+pub mod index;
+pub mod merge;
+pub mod migrate;
+pub mod ops;
+pub mod paths;
+pub mod pomodoro;
+pub mod progress;
+pub mod settings;
+pub mod store;
+pub mod tasks;
+pub mod time_log;
+pub mod timer;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use check::Issue;
+pub use error::{ErrorKind, NekoError};
+pub use paths::{DataDirSource, DataPaths};
+pub use progress::{CounterItem, Direction, Frequency, ProgressEntry, ProgressItem};
+pub use settings::Settings;
+pub use tasks::{Group, Interval, LineEnding, NoteLine, Priority, Task};
+pub use time_log::{AppUsage, DayTimeData};
+
+/// Milliseconds since the Unix epoch, the timestamp unit used in every file.
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Generates an id in the same `task-<millis>-<suffix>` shape the frontend uses.
+pub fn new_task_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    // Cheap mixing so ids created in the same millisecond still differ
+    let mut x = nanos ^ seq.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    x ^= x >> 29;
+    x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x ^= x >> 32;
+
+    format!("task-{}-{}", now_millis(), to_base36(x % 36u64.pow(5), 5))
+}
+
+fn to_base36(mut n: u64, width: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut buf = vec![b'0'; width];
+    for slot in buf.iter_mut().rev() {
+        *slot = DIGITS[(n % 36) as usize];
+        n /= 36;
+    }
+    String::from_utf8(buf).unwrap_or_default()
+}
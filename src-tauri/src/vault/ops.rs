@@ -0,0 +1,238 @@
+// Task operations shared by the app backend and the CLI. They mirror what
+// the group store does in the frontend so both produce the same files.
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::error::{NekoError, Result};
+use super::tasks::{Group, Priority, Task};
+use super::{new_task_id, now_millis};
+
+/// Finds a group by id, falling back to an exact name match.
+pub fn find_group<'a>(groups: &'a [Group], query: &str) -> Option<&'a Group> {
+    groups
+        .iter()
+        .find(|g| g.id == query)
+        .or_else(|| groups.iter().find(|g| g.name == query))
+}
+
+/// Locates a task by exact id or unique id prefix across all groups.
+/// Returns `(group index, task index)`.
+pub fn find_task(groups: &[Group], query: &str) -> Result<(usize, usize)> {
+    let mut matches = Vec::new();
+    for (gi, group) in groups.iter().enumerate() {
+        for (ti, task) in group.tasks.iter().enumerate() {
+            if task.id == query {
+                return Ok((gi, ti));
+            }
+            if task.id.starts_with(query) {
+                matches.push((gi, ti));
+            }
+        }
+    }
+    match matches.as_slice() {
+        [single] => Ok(*single),
+        [] => Err(NekoError::not_found(format!("no task matches {:?}", query)).with_task(query)),
+        _ => Err(
+            NekoError::invalid(format!("{:?} matches {} tasks, use a longer id", query, matches.len()))
+                .with_task(query),
+        ),
+    }
+}
+
+// Rewrite the `order` of one sibling list to 0..n, keeping relative order
+pub(super) fn renumber(group: &mut Group, parent: Option<&str>) {
+    let mut siblings: Vec<usize> = (0..group.tasks.len())
+        .filter(|&i| group.tasks[i].parent_id.as_deref() == parent)
+        .collect();
+    siblings.sort_by_key(|&i| group.tasks[i].order);
+    for (order, i) in siblings.into_iter().enumerate() {
+        group.tasks[i].order = order as i64;
+    }
+}
+
+/// Appends a task (or subtask of `parent`) and returns its id.
+pub fn add_task(group: &mut Group, content: &str, parent: Option<&str>, priority: Option<Priority>) -> Result<String> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(NekoError::invalid("task text is empty"));
+    }
+    if let Some(parent) = parent {
+        if !group.tasks.iter().any(|t| t.id == parent) {
+            return Err(
+                NekoError::not_found(format!("parent {:?} is not in group {:?}", parent, group.id)).with_task(parent),
+            );
+        }
+    }
+
+    let order = group.tasks.iter().filter(|t| t.parent_id.as_deref() == parent).count() as i64;
+    let id = new_task_id();
+    group.tasks.push(Task {
+        id: id.clone(),
+        content: content.to_string(),
+        completed: false,
+        created_at: now_millis(),
+        completed_at: None,
+        scheduled_time: None,
+        order,
+        parent_id: parent.map(str::to_string),
+        collapsed: false,
+        priority,
+        estimated_minutes: None,
+        actual_minutes: None,
+        intervals: Vec::new(),
+        extra: Vec::new(),
+    });
+    group.updated_at = now_millis();
+    Ok(id)
+}
+
+/// Checks or unchecks a task. Like the app, top-level tasks are then
+/// re-sorted so open ones come before completed ones.
+pub fn set_completed(group: &mut Group, id: &str, completed: bool) -> bool {
+    let Some(task) = group.tasks.iter_mut().find(|t| t.id == id) else {
+        return false;
+    };
+    task.completed = completed;
+    task.completed_at = completed.then(now_millis);
+
+    let mut top: Vec<usize> = (0..group.tasks.len())
+        .filter(|&i| group.tasks[i].parent_id.is_none())
+        .collect();
+    top.sort_by_key(|&i| (group.tasks[i].completed, group.tasks[i].order));
+    for (order, i) in top.into_iter().enumerate() {
+        group.tasks[i].order = order as i64;
+    }
+    group.updated_at = now_millis();
+    true
+}
+
+/// Ids of `id` and everything nested under it, parents before children.
+pub fn subtree_ids(group: &Group, id: &str) -> Vec<String> {
+    let mut ids = vec![id.to_string()];
+    let mut seen: HashSet<String> = ids.iter().cloned().collect();
+    let mut i = 0;
+    while i < ids.len() {
+        for task in &group.tasks {
+            if task.parent_id.as_deref() == Some(ids[i].as_str()) && seen.insert(task.id.clone()) {
+                ids.push(task.id.clone());
+            }
+        }
+        i += 1;
+    }
+    ids
+}
+
+/// Removes a task with its descendants. The root comes first and is
+/// detached from its parent; the rest keep their `parent:` links.
+pub fn take_subtree(group: &mut Group, id: &str) -> Vec<Task> {
+    let Some(parent) = group.tasks.iter().find(|t| t.id == id).map(|t| t.parent_id.clone()) else {
+        return Vec::new();
+    };
+    let ids = subtree_ids(group, id);
+    let wanted: HashSet<&str> = ids.iter().map(String::as_str).collect();
+
+    let (mut taken, kept): (Vec<Task>, Vec<Task>) = group.tasks.drain(..).partition(|t| wanted.contains(t.id.as_str()));
+    group.tasks = kept;
+    taken.sort_by_key(|t| ids.iter().position(|i| *i == t.id));
+    if let Some(root) = taken.first_mut() {
+        root.parent_id = None;
+    }
+
+    renumber(group, parent.as_deref());
+    group.updated_at = now_millis();
+    taken
+}
+
+/// Inserts a subtree from `take_subtree` as a top-level task at `index`
+/// (or at the end).
+pub fn insert_subtree(group: &mut Group, mut tasks: Vec<Task>, index: Option<usize>) {
+    let Some(root) = tasks.first_mut() else {
+        return;
+    };
+    let top_count = group.tasks.iter().filter(|t| t.parent_id.is_none()).count();
+    let index = index.unwrap_or(top_count).min(top_count) as i64;
+
+    for task in group.tasks.iter_mut().filter(|t| t.parent_id.is_none()) {
+        if task.order >= index {
+            task.order += 1;
+        }
+    }
+    root.parent_id = None;
+    root.order = index;
+
+    group.tasks.extend(tasks);
+    renumber(group, None);
+    group.updated_at = now_millis();
+}
+
+/// Moves a task and its descendants to the end of another group.
+pub fn move_task(from: &mut Group, to: &mut Group, id: &str) -> Result<Vec<String>> {
+    let taken = take_subtree(from, id);
+    if taken.is_empty() {
+        return Err(NekoError::not_found(format!("task {:?} is not in group {:?}", id, from.id)).with_task(id));
+    }
+    let ids = taken.iter().map(|t| t.id.clone()).collect();
+    insert_subtree(to, taken, None);
+    Ok(ids)
+}
+
+/// Resolves a multi-selection to `(group index, id)` pairs in selection
+/// order, dropping duplicates and tasks nested under another selected task
+/// (they come along with their ancestor anyway).
+pub fn selection_roots(groups: &[Group], ids: &[String]) -> Result<Vec<(usize, String)>> {
+    let mut roots: Vec<(usize, String)> = Vec::new();
+    for id in ids {
+        let gi = groups
+            .iter()
+            .position(|g| g.tasks.iter().any(|t| t.id == *id))
+            .ok_or_else(|| NekoError::not_found(format!("no task {:?}", id)).with_task(id))?;
+        if !roots.iter().any(|(_, r)| r == id) {
+            roots.push((gi, id.clone()));
+        }
+    }
+    let nested: HashSet<String> = roots
+        .iter()
+        .flat_map(|(gi, id)| subtree_ids(&groups[*gi], id).into_iter().skip(1))
+        .collect();
+    roots.retain(|(_, id)| !nested.contains(id));
+    Ok(roots)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchMove {
+    /// Every task that moved, descendants included.
+    pub moved: Vec<String>,
+    /// Ids of the groups that changed and need saving.
+    pub groups: Vec<String>,
+}
+
+/// Moves several tasks, each with its descendants, into group `to`. They
+/// become top-level tasks starting at `index` (or the end), in selection
+/// order. Nothing changes unless every id resolves.
+pub fn move_tasks(groups: &mut [Group], ids: &[String], to: &str, index: Option<usize>) -> Result<BatchMove> {
+    let target = groups
+        .iter()
+        .position(|g| g.id == to)
+        .ok_or_else(|| NekoError::not_found(format!("no group {:?}", to)))?;
+    let roots = selection_roots(groups, ids)?;
+
+    let mut changed = vec![groups[target].id.clone()];
+    let mut subtrees = Vec::new();
+    for (gi, id) in &roots {
+        if !changed.contains(&groups[*gi].id) {
+            changed.push(groups[*gi].id.clone());
+        }
+        subtrees.push(take_subtree(&mut groups[*gi], id));
+    }
+
+    let mut moved = Vec::new();
+    let mut at = index;
+    for subtree in subtrees {
+        moved.extend(subtree.iter().map(|t| t.id.clone()));
+        insert_subtree(&mut groups[target], subtree, at);
+        at = at.map(|i| i + 1);
+    }
+    Ok(BatchMove { moved, groups: changed })
+}
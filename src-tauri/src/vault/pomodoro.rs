@@ -0,0 +1,94 @@
+// Pomodoro cycles on top of the task timer. A work session runs its task's
+// timer, so the minutes reach the task as an ordinary logged session and are
+// never added a second time; the pomodoro itself is only counted, per local
+// day, in `time-log.md`. State lives in `timers.json` with the timers.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PomodoroConfig {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// Every this many pomodoros the break is a long one.
+    pub long_break_every: u32,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        PomodoroConfig {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+        }
+    }
+}
+
+impl PomodoroConfig {
+    /// Length of a phase; a zero in the settings still lasts a minute.
+    pub fn millis(&self, phase: Phase) -> i64 {
+        let minutes = match phase {
+            Phase::Work => self.work_minutes,
+            Phase::ShortBreak => self.short_break_minutes,
+            Phase::LongBreak => self.long_break_minutes,
+        };
+        i64::from(minutes.max(1)) * 60_000
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub phase: Phase,
+    /// The task a work session times; breaks keep it to resume with.
+    pub task_id: Option<String>,
+    pub started_at: i64,
+    pub ends_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Pomodoro {
+    /// The lengths the current cycle was started with.
+    pub config: PomodoroConfig,
+    pub session: Option<Session>,
+    /// Pomodoros finished since the last long break.
+    pub streak: u32,
+}
+
+impl Pomodoro {
+    pub fn begin(&mut self, phase: Phase, task_id: Option<String>, now: i64) {
+        self.session = Some(Session {
+            phase,
+            task_id,
+            started_at: now,
+            ends_at: now + self.config.millis(phase),
+        });
+    }
+
+    /// The task of a work session in progress.
+    pub fn working_on(&self) -> Option<&str> {
+        self.session
+            .as_ref()
+            .filter(|s| s.phase == Phase::Work)
+            .and_then(|s| s.task_id.as_deref())
+    }
+
+    /// The break that follows a finished pomodoro.
+    pub fn next_break(&self) -> Phase {
+        if self.streak.is_multiple_of(self.config.long_break_every.max(1)) {
+            Phase::LongBreak
+        } else {
+            Phase::ShortBreak
+        }
+    }
+}
@@ -0,0 +1,319 @@
+// Progress file: `progress/progress.md`
+//
+// # 进度列表
+//
+// ## Read SICP
+// - id: abc
+// - type: progress
+// - direction: increment
+// - total: 100
+// - step: 1
+// - unit: 页
+// - current: 12
+// - todayCount: 3
+// - createdAt: 1733000000000
+
+use serde::{Deserialize, Serialize};
+
+use super::now_millis;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Increment,
+    Decrement,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Increment => "increment",
+            Direction::Decrement => "decrement",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressItem {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub direction: Direction,
+    pub total: i64,
+    pub step: i64,
+    pub unit: String,
+    pub current: i64,
+    pub today_count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_update_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<i64>,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterItem {
+    pub id: String,
+    pub title: String,
+    pub step: i64,
+    pub unit: String,
+    pub current: i64,
+    pub today_count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_update_date: Option<String>,
+    pub frequency: Frequency,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<(String, String)>,
+}
+
+/// One `## ` block of the progress file, tagged by its `type:` line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ProgressEntry {
+    Progress(ProgressItem),
+    Counter(CounterItem),
+}
+
+impl ProgressEntry {
+    pub fn id(&self) -> &str {
+        match self {
+            ProgressEntry::Progress(item) => &item.id,
+            ProgressEntry::Counter(item) => &item.id,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        match self {
+            ProgressEntry::Progress(item) => &item.title,
+            ProgressEntry::Counter(item) => &item.title,
+        }
+    }
+
+    /// Applies `times` clicks of the +/- button, as the progress page does:
+    /// progress moves by its step in its direction (clamped to 0..=total),
+    /// counters by their step. `today` is the `lastUpdateDate` label.
+    pub fn bump(&mut self, times: i64, today: &str) {
+        let (delta, current, today_count, last_update) = match self {
+            ProgressEntry::Progress(item) => {
+                let step = match item.direction {
+                    Direction::Increment => item.step,
+                    Direction::Decrement => -item.step,
+                };
+                (
+                    step * times,
+                    &mut item.current,
+                    &mut item.today_count,
+                    &mut item.last_update_date,
+                )
+            }
+            ProgressEntry::Counter(item) => (
+                item.step * times,
+                &mut item.current,
+                &mut item.today_count,
+                &mut item.last_update_date,
+            ),
+        };
+
+        let is_new_day = last_update.as_deref() != Some(today);
+        *today_count = if is_new_day {
+            delta.abs()
+        } else {
+            *today_count + delta.abs()
+        };
+        *current += delta;
+        *last_update = Some(today.to_string());
+
+        if let ProgressEntry::Progress(item) = self {
+            item.current = item.current.clamp(0, item.total.max(0));
+        }
+    }
+}
+
+/// Today's date in the format the frontend stores in `lastUpdateDate`
+/// (JavaScript's `Date.prototype.toDateString`, e.g. `Fri Oct 16 2026`).
+pub fn today_label() -> String {
+    chrono::Local::now().format("%a %b %d %Y").to_string()
+}
+
+// Raw key/value pairs of a block before we know its type
+struct Block {
+    title: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Block {
+    fn take(&mut self, key: &str) -> Option<String> {
+        let pos = self.fields.iter().position(|(k, _)| k == key)?;
+        Some(self.fields.remove(pos).1)
+    }
+
+    fn take_int(&mut self, key: &str) -> Option<i64> {
+        self.take(key).and_then(|v| v.parse().ok())
+    }
+
+    fn take_text(&mut self, key: &str) -> Option<String> {
+        self.take(key).filter(|v| !v.is_empty())
+    }
+
+    fn into_entry(mut self) -> Option<ProgressEntry> {
+        let id = self.take_text("id")?;
+        let kind = self.take("type")?;
+        if self.title.is_empty() {
+            return None;
+        }
+
+        let step = self.take_int("step").filter(|s| *s != 0).unwrap_or(1);
+        let unit = self.take("unit").unwrap_or_default();
+        let current = self.take_int("current").unwrap_or(0);
+        let today_count = self.take_int("todayCount").unwrap_or(0);
+        let last_update_date = self.take_text("lastUpdateDate");
+        let created_at = self.take_int("createdAt").unwrap_or_else(now_millis);
+
+        match kind.as_str() {
+            "progress" => Some(ProgressEntry::Progress(ProgressItem {
+                id,
+                note: self.take_text("note"),
+                direction: match self.take("direction").as_deref() {
+                    Some("decrement") => Direction::Decrement,
+                    _ => Direction::Increment,
+                },
+                total: self.take_int("total").filter(|t| *t != 0).unwrap_or(100),
+                step,
+                unit,
+                current,
+                today_count,
+                last_update_date,
+                start_date: self.take_int("startDate"),
+                end_date: self.take_int("endDate"),
+                created_at,
+                title: self.title,
+                extra: self.fields,
+            })),
+            "counter" => Some(ProgressEntry::Counter(CounterItem {
+                id,
+                step,
+                unit,
+                current,
+                today_count,
+                last_update_date,
+                frequency: match self.take("frequency").as_deref() {
+                    Some("weekly") => Frequency::Weekly,
+                    Some("monthly") => Frequency::Monthly,
+                    _ => Frequency::Daily,
+                },
+                created_at,
+                title: self.title,
+                extra: self.fields,
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the progress file. Blocks without an id, type or title are skipped.
+pub fn parse_progress(content: &str) -> Vec<ProgressEntry> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for raw in content.lines() {
+        if let Some(title) = raw.strip_prefix("## ") {
+            blocks.push(Block {
+                title: title.trim().to_string(),
+                fields: Vec::new(),
+            });
+            continue;
+        }
+        let Some(block) = blocks.last_mut() else {
+            continue;
+        };
+        let Some(field) = raw.trim().strip_prefix("- ") else {
+            continue;
+        };
+        if let Some((key, value)) = field.split_once(':') {
+            block.fields.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    blocks.into_iter().filter_map(Block::into_entry).collect()
+}
+
+pub fn serialize_progress(items: &[ProgressEntry]) -> String {
+    let mut lines = vec!["# 进度列表".to_string(), String::new()];
+
+    for entry in items {
+        match entry {
+            ProgressEntry::Progress(item) => {
+                lines.push(format!("## {}", item.title));
+                lines.push(format!("- id: {}", item.id));
+                lines.push("- type: progress".to_string());
+                if let Some(note) = &item.note {
+                    lines.push(format!("- note: {}", note));
+                }
+                lines.push(format!("- direction: {}", item.direction.as_str()));
+                lines.push(format!("- total: {}", item.total));
+                lines.push(format!("- step: {}", item.step));
+                lines.push(format!("- unit: {}", item.unit));
+                lines.push(format!("- current: {}", item.current));
+                lines.push(format!("- todayCount: {}", item.today_count));
+                if let Some(date) = &item.last_update_date {
+                    lines.push(format!("- lastUpdateDate: {}", date));
+                }
+                if let Some(start) = item.start_date {
+                    lines.push(format!("- startDate: {}", start));
+                }
+                if let Some(end) = item.end_date {
+                    lines.push(format!("- endDate: {}", end));
+                }
+                lines.push(format!("- createdAt: {}", item.created_at));
+                for (key, value) in &item.extra {
+                    lines.push(format!("- {}: {}", key, value));
+                }
+            }
+            ProgressEntry::Counter(item) => {
+                lines.push(format!("## {}", item.title));
+                lines.push(format!("- id: {}", item.id));
+                lines.push("- type: counter".to_string());
+                lines.push(format!("- step: {}", item.step));
+                lines.push(format!("- unit: {}", item.unit));
+                lines.push(format!("- current: {}", item.current));
+                lines.push(format!("- todayCount: {}", item.today_count));
+                if let Some(date) = &item.last_update_date {
+                    lines.push(format!("- lastUpdateDate: {}", date));
+                }
+                lines.push(format!("- frequency: {}", item.frequency.as_str()));
+                lines.push(format!("- createdAt: {}", item.created_at));
+                for (key, value) in &item.extra {
+                    lines.push(format!("- {}: {}", key, value));
+                }
+            }
+        }
+        lines.push(String::new());
+    }
+
+    lines.join("\n")
+}
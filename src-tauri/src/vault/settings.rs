@@ -0,0 +1,72 @@
+// Backend settings stored next to the vault data in `settings.json`
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::atomic;
+use super::paths::DataPaths;
+use super::pomodoro::PomodoroConfig;
+use super::timer::IdleConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Write a task to a `.md` file when it is dropped outside the window.
+    pub export_on_drop: bool,
+    /// Where dropped tasks and diagnostics are written, relative to the
+    /// vault root; `outbox` when unset.
+    pub outbox: Option<PathBuf>,
+    /// Write bare `- [ ] text` lines and keep ids and timestamps in
+    /// `.nekotick/index.json` instead of a comment on every line.
+    pub clean_markdown: bool,
+    pub pomodoro: PomodoroConfig,
+    /// When running timers pause for an idle user.
+    pub idle: IdleConfig,
+    /// Log how long each focused application is used in `time-log.md`.
+    pub track_apps: bool,
+    /// Listen on localhost for the browser extension's active-tab reports.
+    pub track_websites: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            export_on_drop: true,
+            outbox: None,
+            clean_markdown: false,
+            pomodoro: PomodoroConfig::default(),
+            idle: IdleConfig::default(),
+            track_apps: false,
+            track_websites: false,
+        }
+    }
+}
+
+impl Settings {
+    /// The folder exports go to. A configured outbox may still lead out of
+    /// the vault, so check it with `ensure_inside` before writing there.
+    pub fn outbox_dir(&self, paths: &DataPaths) -> PathBuf {
+        paths.root.join(self.outbox.as_deref().unwrap_or(Path::new("outbox")))
+    }
+}
+
+/// Missing or unreadable settings fall back to the defaults.
+pub fn load(paths: &DataPaths) -> Settings {
+    let path = paths.settings_file();
+    if paths.ensure_inside(&path).is_err() {
+        return Settings::default();
+    }
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(paths: &DataPaths, settings: &Settings) -> io::Result<()> {
+    let path = paths.settings_file();
+    paths.ensure_inside(&path)?;
+    let json = serde_json::to_vec_pretty(settings)?;
+    atomic::write_atomic(&path, &json)
+}
@@ -0,0 +1,275 @@
+// Loading and saving vault files on disk
+use std::fs;
+use std::io;
+use std::sync::Mutex;
+
+use super::atomic::{self, BACKUP_GENERATIONS};
+use super::index::{self, VaultIndex};
+use super::paths::DataPaths;
+use super::progress::{self, ProgressEntry};
+use super::settings;
+use super::tasks::{self, Group};
+use super::time_log::{self, DayTimeData};
+
+/// Group ids double as file names, so they must stay inside `tasks/`.
+pub fn is_valid_group_id(id: &str) -> bool {
+    !id.is_empty() && !id.starts_with('.') && !id.contains(['/', '\\', '\0'])
+}
+
+fn check_group_id(id: &str) -> io::Result<()> {
+    if is_valid_group_id(id) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid group id: {:?}", id),
+        ))
+    }
+}
+
+fn group_is_good(bytes: &[u8]) -> bool {
+    std::str::from_utf8(bytes).is_ok_and(tasks::is_well_formed)
+}
+
+/// Ids of every group file in `tasks/`, sorted.
+pub fn list_group_ids(paths: &DataPaths) -> io::Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(&paths.tasks)? {
+        let path = entry?.path();
+        if !path.is_file() || atomic::is_temp_file(&path) {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if let Some(id) = name.strip_suffix(".md") {
+            if is_valid_group_id(id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+    ids.sort();
+    Ok(ids)
+}
+
+/// Loads one group, falling back to the newest healthy `.bak` if the file
+/// itself looks damaged.
+pub fn load_group(paths: &DataPaths, id: &str) -> io::Result<Group> {
+    load_group_with(paths, id, &index::load(paths))
+}
+
+fn load_group_with(paths: &DataPaths, id: &str, index: &VaultIndex) -> io::Result<Group> {
+    check_group_id(id)?;
+    let path = paths.group_file(id);
+    paths.ensure_inside(&path)?;
+    let (bytes, _) = atomic::read_with_fallback(&path, BACKUP_GENERATIONS, group_is_good)?;
+    let entries = index.groups.get(id).map(Vec::as_slice).unwrap_or_default();
+    Ok(index::parse_group(id, &String::from_utf8_lossy(&bytes), entries))
+}
+
+/// Loads every group. A file that can't be read, such as a link out of the
+/// vault, is logged and left out rather than failing the whole vault.
+pub fn load_groups(paths: &DataPaths) -> io::Result<Vec<Group>> {
+    recover(paths)?;
+    let index = index::load(paths);
+    let mut groups = Vec::new();
+    for id in list_group_ids(paths)? {
+        match load_group_with(paths, &id, &index) {
+            Ok(group) => groups.push(group),
+            Err(e) => log::warn!("skipping group {}: {}", id, e),
+        }
+    }
+    Ok(groups)
+}
+
+/// Serializes groups in the format the settings ask for, as `(id, content)`
+/// pairs for `write_group_files`. In clean Markdown mode their index entries
+/// are saved first; should the file write then fail, the loader still finds
+/// the old lines by hash and position.
+pub fn group_files(paths: &DataPaths, groups: &[&Group]) -> io::Result<Vec<(String, String)>> {
+    let clean = settings::load(paths).clean_markdown;
+    let mut index = index::load(paths);
+    let before = index.clone();
+    let mut files = Vec::with_capacity(groups.len());
+    for group in groups {
+        let content = if clean {
+            index.groups.insert(group.id.clone(), index::entries(group));
+            tasks::serialize_group_clean(group)
+        } else {
+            index.groups.remove(&group.id);
+            tasks::serialize_group(group)
+        };
+        files.push((group.id.clone(), content));
+    }
+    if index != before {
+        index::save(paths, &index)?;
+    }
+    Ok(files)
+}
+
+pub fn save_group(paths: &DataPaths, group: &Group) -> io::Result<()> {
+    // An older batch must land before this save, not be replayed over it
+    recover(paths)?;
+    for (id, content) in group_files(paths, &[group])? {
+        write_group_file(paths, &id, &content)?;
+    }
+    Ok(())
+}
+
+/// Writes already-serialized group content for `id`.
+pub fn write_group_file(paths: &DataPaths, id: &str, content: &str) -> io::Result<()> {
+    check_group_id(id)?;
+    fs::create_dir_all(&paths.tasks)?;
+    let path = paths.group_file(id);
+    paths.ensure_inside(&path)?;
+    atomic::write_with_backups(&path, content.as_bytes(), BACKUP_GENERATIONS, group_is_good)
+}
+
+/// Removes a group file. Its `.bak` generations stay behind as a safety net.
+pub fn delete_group(paths: &DataPaths, id: &str) -> io::Result<()> {
+    check_group_id(id)?;
+    recover(paths)?;
+    let path = paths.group_file(id);
+    paths.ensure_inside(&path)?;
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        result => result?,
+    }
+    let mut index = index::load(paths);
+    if index.groups.remove(id).is_some() {
+        index::save(paths, &index)?;
+    }
+    Ok(())
+}
+
+/// Writes several group files as one unit. The new contents are journaled
+/// first, so if we die halfway `recover` finishes the job on the next load
+/// instead of leaving a task in both groups or in neither. A write that
+/// fails while we're alive puts the files already written back instead.
+pub fn write_group_files(paths: &DataPaths, files: &[(String, String)]) -> io::Result<()> {
+    let mut previous = Vec::with_capacity(files.len());
+    for (id, _) in files {
+        check_group_id(id)?;
+        let path = paths.group_file(id);
+        paths.ensure_inside(&path)?;
+        previous.push(fs::read(&path).ok());
+    }
+    let journal = paths.journal_file();
+    paths.ensure_inside(&journal)?;
+    atomic::write_atomic(&journal, &serde_json::to_vec(files)?)?;
+    for (written, (id, content)) in files.iter().enumerate() {
+        if let Err(e) = write_group_file(paths, id, content) {
+            roll_back(paths, &files[..written], &previous);
+            // Left behind, it would be replayed over every later save
+            let _ = fs::remove_file(&journal);
+            return Err(e);
+        }
+    }
+    fs::remove_file(&journal)
+}
+
+// Best effort: restores what `written` held before a failed batch
+fn roll_back(paths: &DataPaths, written: &[(String, String)], previous: &[Option<Vec<u8>>]) {
+    for ((id, _), before) in written.iter().zip(previous) {
+        let path = paths.group_file(id);
+        let result = match before {
+            Some(bytes) => atomic::write_atomic(&path, bytes),
+            None => fs::remove_file(&path),
+        };
+        if let Err(e) = result {
+            log::warn!("failed to roll back group {}: {}", id, e);
+        }
+    }
+}
+
+pub fn save_groups(paths: &DataPaths, groups: &[&Group]) -> io::Result<()> {
+    write_group_files(paths, &group_files(paths, groups)?)
+}
+
+/// Replays a journal left behind by an interrupted `write_group_files`.
+/// Returns whether there was one.
+pub fn recover(paths: &DataPaths) -> io::Result<bool> {
+    let journal = paths.journal_file();
+    paths.ensure_inside(&journal)?;
+    let bytes = match fs::read(&journal) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    // The journal itself is written atomically, so it is either whole or absent
+    let replayed = serde_json::from_slice::<Vec<(String, String)>>(&bytes)
+        .map_err(io::Error::from)
+        .and_then(|files| {
+            files
+                .iter()
+                .try_for_each(|(id, content)| write_group_file(paths, id, content))
+        });
+    // Even after a failed replay: kept, it would be replayed over later saves
+    fs::remove_file(&journal)?;
+    replayed.map(|()| true)
+}
+
+fn progress_is_good(bytes: &[u8]) -> bool {
+    std::str::from_utf8(bytes).is_ok_and(|s| s.trim_start().starts_with("# "))
+}
+
+pub fn load_progress(paths: &DataPaths) -> io::Result<Vec<ProgressEntry>> {
+    let path = paths.progress_file();
+    paths.ensure_inside(&path)?;
+    match atomic::read_with_fallback(&path, BACKUP_GENERATIONS, progress_is_good) {
+        Ok((bytes, _)) => Ok(progress::parse_progress(&String::from_utf8_lossy(&bytes))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn save_progress(paths: &DataPaths, items: &[ProgressEntry]) -> io::Result<()> {
+    fs::create_dir_all(&paths.progress)?;
+    let path = paths.progress_file();
+    paths.ensure_inside(&path)?;
+    atomic::write_with_backups(
+        &path,
+        progress::serialize_progress(items).as_bytes(),
+        BACKUP_GENERATIONS,
+        progress_is_good,
+    )
+}
+
+pub fn load_time_log(paths: &DataPaths) -> io::Result<Vec<DayTimeData>> {
+    let path = paths.time_log_file();
+    paths.ensure_inside(&path)?;
+    match fs::read(&path) {
+        Ok(bytes) => Ok(time_log::parse_time_log(&String::from_utf8_lossy(&bytes))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads the time log, lets `change` edit it and writes it back when it
+/// returns true. Several threads add to the log, so edits are serialized.
+pub fn update_time_log<F>(paths: &DataPaths, change: F) -> io::Result<()>
+where
+    F: FnOnce(&mut Vec<DayTimeData>) -> bool,
+{
+    static LOCK: Mutex<()> = Mutex::new(());
+    let _guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut days = load_time_log(paths)?;
+    if !change(&mut days) {
+        return Ok(());
+    }
+    fs::create_dir_all(&paths.time_tracker)?;
+    atomic::write_atomic(&paths.time_log_file(), time_log::serialize_time_log(&days).as_bytes())
+}
+
+/// Counts pomodoros that ended at `ends` on their local days in the time log.
+pub fn record_pomodoros(paths: &DataPaths, ends: &[i64]) -> io::Result<()> {
+    if ends.is_empty() {
+        return Ok(());
+    }
+    update_time_log(paths, |days| {
+        for end in ends {
+            time_log::add_pomodoros(days, &time_log::local_day(*end), 1);
+        }
+        true
+    })
+}
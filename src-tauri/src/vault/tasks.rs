@@ -0,0 +1,657 @@
+// Group files: `tasks/<group-id>.md`
+//
+// # 收集箱
+//
+// format: 1
+// pinned: false
+// created: 1733000000000
+// updated: 1733000000000
+//
+// - [ ] Task <!--id:task-1,created:1733000000000,order:0,est:30,act:5-->
+//   - [x] Subtask <!--id:task-2,created:1733000000000,order:0,parent:task-1,log:1733000000000-1733000900000-->
+//
+// `est` and `act` are minutes; `log` lists timed sessions as `start-end`
+// pairs in milliseconds, separated by `;`.
+//
+// Hand-written files may leave out the metadata: nesting then comes from
+// indentation (tabs or any number of spaces) and `*` / `+` bullets work too.
+// In clean Markdown mode every line is written that way and the metadata
+// lives in the index, see `index.rs`.
+//
+// Files without a `format:` line predate it and may be in either legacy
+// dialect: the same comma metadata, or the old single-file one with
+// `{est:N} {act:N} {done:DATE}` tags and `<!-- id:x created:y -->`. Tags
+// become the `est` / `act` keys and `completedAt`; saving writes `format: 1`.
+//
+// Everything else in the body (notes, blank lines, foreign headings) is kept
+// after the task it followed, and a file keeps its line ending, so a file
+// in this format is written back byte for byte.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use super::{new_task_id, now_millis};
+
+/// Written as `format: N` in every group file's header.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Red,
+    Yellow,
+    Purple,
+    Green,
+    Default,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Red => "red",
+            Priority::Yellow => "yellow",
+            Priority::Purple => "purple",
+            Priority::Green => "green",
+            Priority::Default => "default",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "red" => Some(Priority::Red),
+            "yellow" => Some(Priority::Yellow),
+            "purple" => Some(Priority::Purple),
+            "green" => Some(Priority::Green),
+            "default" => Some(Priority::Default),
+            _ => None,
+        }
+    }
+}
+
+/// A timed work session on a task, in milliseconds since the epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn millis(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub content: String,
+    pub completed: bool,
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_time: Option<String>,
+    pub order: i64,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub collapsed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Planned effort in minutes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+    /// Minutes logged by hand, on top of `intervals`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_minutes: Option<u32>,
+    /// Timed sessions, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<Interval>,
+    /// Metadata keys we don't understand, written back untouched.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    pub id: String,
+    pub name: String,
+    pub pinned: bool,
+    pub tasks: Vec<Task>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Non-task lines of the body (notes, blank lines, foreign headers) in
+    /// file order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<NoteLine>,
+    /// The line ending the file used, written back on save.
+    #[serde(default, skip_serializing_if = "LineEnding::is_lf")]
+    pub line_ending: LineEnding,
+    /// Whether the file ends with a line ending.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub final_newline: bool,
+}
+
+/// A line of the group file that isn't a task or part of the header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteLine {
+    /// The task whose line it followed; `None` above the first task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
+
+    pub fn is_lf(&self) -> bool {
+        *self == LineEnding::Lf
+    }
+}
+
+impl Task {
+    /// Time spent: the logged sessions plus the minutes entered by hand.
+    pub fn actual_millis(&self) -> i64 {
+        let logged: i64 = self.intervals.iter().map(Interval::millis).sum();
+        logged + i64::from(self.actual_minutes.unwrap_or(0)) * 60_000
+    }
+}
+
+impl Group {
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        let now = now_millis();
+        Group {
+            id: id.into(),
+            name: name.into(),
+            pinned: false,
+            tasks: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            extra: Vec::new(),
+            line_ending: LineEnding::Lf,
+            final_newline: false,
+        }
+    }
+}
+
+// Split a task line into (checked, body) if it is a checkbox item
+pub(super) fn parse_checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = line.strip_prefix(['-', '*', '+'])?.strip_prefix(" [")?;
+    let mut chars = rest.chars();
+    let mark = chars.next()?;
+    let rest = chars.as_str().strip_prefix("] ")?;
+    let completed = match mark {
+        ' ' => false,
+        'x' | 'X' => true,
+        _ => return None,
+    };
+    if rest.trim().is_empty() {
+        return None;
+    }
+    Some((completed, rest))
+}
+
+// Split `content <!--meta-->` at the last comment so `<!--` inside the text survives
+pub(super) fn split_meta(body: &str) -> (&str, Option<&str>) {
+    let trimmed = body.trim_end();
+    if let Some(inner) = trimmed.strip_suffix("-->") {
+        if let Some(start) = inner.rfind("<!--") {
+            return (inner[..start].trim(), Some(&inner[start + 4..]));
+        }
+    }
+    (body.trim(), None)
+}
+
+// `id:a,created:1` or, in the legacy dialect, `id:a created:1`
+fn parse_meta(meta: &str, legacy: bool) -> Vec<(String, String)> {
+    let pairs: Vec<&str> = if !legacy || meta.contains(',') {
+        meta.split(',').collect()
+    } else {
+        meta.split_whitespace().collect()
+    };
+    pairs
+        .into_iter()
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once(':')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Leading whitespace in columns, a tab counting as four.
+pub(super) fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+// A task line before its place in the hierarchy is known
+struct ParsedLine {
+    task: Task,
+    indent: usize,
+    has_id: bool,
+    has_order: bool,
+    // The first item after an unindented paragraph, which ends any list
+    starts_list: bool,
+}
+
+// Pulls `{key:value}` out of legacy task text
+fn take_tag(content: &mut String, key: &str) -> Option<String> {
+    let open = format!("{{{}:", key);
+    let start = content.find(&open)?;
+    let len = content[start..].find('}')? + 1;
+    let value = content[start + open.len()..start + len - 1].to_string();
+    content.replace_range(start..start + len, "");
+    Some(value)
+}
+
+// `start-end;start-end`
+fn parse_intervals(value: &str) -> Option<Vec<Interval>> {
+    value
+        .split(';')
+        .filter(|s| !s.trim().is_empty())
+        .map(|pair| {
+            let (start, end) = pair.trim().split_once('-')?;
+            Some(Interval {
+                start: start.parse().ok()?,
+                end: end.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// `{done:2024-01-15}` -> local midnight of that day
+fn legacy_done(date: &str) -> Option<i64> {
+    let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+    let midnight = Local.from_local_datetime(&day.and_hms_opt(0, 0, 0)?).earliest()?;
+    Some(midnight.timestamp_millis())
+}
+
+fn parse_task(completed: bool, body: &str, indent: usize, legacy: bool) -> ParsedLine {
+    let (content, meta) = split_meta(body);
+    let mut content = content.to_string();
+    let mut tags = Vec::new();
+    if legacy {
+        for key in ["est", "act", "done"] {
+            if let Some(value) = take_tag(&mut content, key) {
+                tags.push((key, value));
+            }
+        }
+        content = content.trim().to_string();
+    }
+    let mut task = Task {
+        id: String::new(),
+        content,
+        completed,
+        created_at: 0,
+        completed_at: None,
+        scheduled_time: None,
+        order: 0,
+        parent_id: None,
+        collapsed: false,
+        priority: None,
+        estimated_minutes: None,
+        actual_minutes: None,
+        intervals: Vec::new(),
+        extra: Vec::new(),
+    };
+    let mut has_order = false;
+
+    for (key, value) in meta.map(|m| parse_meta(m, legacy)).unwrap_or_default() {
+        match key.as_str() {
+            "id" if !value.is_empty() => task.id = value,
+            "created" => task.created_at = value.parse().unwrap_or(0),
+            "order" => {
+                if let Ok(order) = value.parse() {
+                    task.order = order;
+                    has_order = true;
+                }
+            }
+            "time" if !value.is_empty() => task.scheduled_time = Some(value),
+            "completedAt" => task.completed_at = value.parse().ok(),
+            "parent" if !value.is_empty() => task.parent_id = Some(value),
+            "collapsed" => task.collapsed = value == "true",
+            "priority" => match Priority::parse(&value) {
+                Some(p) => task.priority = Some(p),
+                None => task.extra.push((key, value)),
+            },
+            "est" => match value.parse() {
+                Ok(minutes) => task.estimated_minutes = Some(minutes),
+                Err(_) => task.extra.push((key, value)),
+            },
+            "act" => match value.parse() {
+                Ok(minutes) => task.actual_minutes = Some(minutes),
+                Err(_) => task.extra.push((key, value)),
+            },
+            "log" => match parse_intervals(&value) {
+                Some(intervals) => task.intervals = intervals,
+                None => task.extra.push((key, value)),
+            },
+            _ => task.extra.push((key, value)),
+        }
+    }
+    for (key, value) in tags {
+        match key {
+            "done" => task.completed_at = task.completed_at.or_else(|| legacy_done(&value)),
+            "est" => task.estimated_minutes = task.estimated_minutes.or(value.parse().ok()),
+            _ => task.actual_minutes = task.actual_minutes.or(value.parse().ok()),
+        }
+    }
+
+    let has_id = !task.id.is_empty();
+    if !has_id {
+        task.id = new_task_id();
+    }
+    if task.created_at == 0 {
+        task.created_at = now_millis();
+    }
+    ParsedLine {
+        task,
+        indent,
+        has_id,
+        has_order,
+        starts_list: false,
+    }
+}
+
+// Fills in what the metadata left out. A missing `parent:` comes from
+// indentation, relative to the enclosing items so any indent width works.
+// An explicit `parent:` always wins: our own files agree with their
+// indentation anyway, and orphans are written unindented on purpose. A
+// missing `order:` is the position among the task's siblings.
+fn resolve_hierarchy(lines: Vec<ParsedLine>) -> Vec<Task> {
+    let mut tasks: Vec<Task> = Vec::with_capacity(lines.len());
+    // (indent, index) of the currently open ancestors
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut missing_order = Vec::new();
+    for line in lines {
+        if line.starts_list {
+            open.clear();
+        }
+        while open.last().is_some_and(|&(w, _)| w >= line.indent) {
+            open.pop();
+        }
+        let mut task = line.task;
+        if task.parent_id.is_none() {
+            task.parent_id = open.last().map(|&(_, i)| tasks[i].id.clone());
+        }
+        if !line.has_order {
+            missing_order.push(tasks.len());
+        }
+        open.push((line.indent, tasks.len()));
+        tasks.push(task);
+    }
+    for i in missing_order {
+        let parent = tasks[i].parent_id.clone();
+        tasks[i].order = tasks[..i].iter().filter(|t| t.parent_id == parent).count() as i64;
+    }
+    tasks
+}
+
+/// Cheap structural check used to tell a healthy file from one that was
+/// truncated mid-write: it must start with the `# ` title and every task
+/// metadata comment must be closed.
+pub fn is_well_formed(content: &str) -> bool {
+    let mut lines = content.lines().map(str::trim).filter(|l| !l.is_empty());
+    if !lines.next().is_some_and(|first| first.starts_with("# ")) {
+        return false;
+    }
+    lines
+        .filter(|line| parse_checkbox(line).is_some())
+        .all(|line| !line.contains("<!--") || line.ends_with("-->"))
+}
+
+/// Parses a group file. Missing metadata is filled in the same way the
+/// frontend always did (fresh id, current time, position as order).
+pub fn parse_group(id: &str, content: &str) -> Group {
+    parse_group_lines(id, content).0
+}
+
+// Also reports, per task, whether its line carried an `id:`
+pub(super) fn parse_group_lines(id: &str, content: &str) -> (Group, Vec<bool>) {
+    let mut group = Group::new(id, "未命名");
+    let mut seen_name = false;
+    let mut lines: Vec<ParsedLine> = Vec::new();
+    let mut after_text = false;
+    let mut format = None;
+    // Blank lines count as notes once the body has begun
+    let mut in_body = false;
+
+    if content.contains("\r\n") {
+        group.line_ending = LineEnding::Crlf;
+    }
+    let content = match content.strip_suffix('\n') {
+        Some(rest) => {
+            group.final_newline = true;
+            rest
+        }
+        None => content,
+    };
+    for raw in content.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        let line = raw.trim();
+        if line.is_empty() {
+            if in_body {
+                push_note(&mut group, &lines, raw);
+            }
+            continue;
+        }
+
+        if let Some((completed, body)) = parse_checkbox(line) {
+            in_body = true;
+            let mut line = parse_task(completed, body, indent_width(raw), format.is_none());
+            line.starts_list = std::mem::take(&mut after_text);
+            lines.push(line);
+            continue;
+        }
+
+        if !seen_name {
+            if let Some(name) = line.strip_prefix("# ") {
+                group.name = name.trim().to_string();
+                seen_name = true;
+                continue;
+            }
+        }
+        if let Some(value) = line.strip_prefix("format:").and_then(|v| v.trim().parse::<u32>().ok()) {
+            if value > FORMAT_VERSION {
+                log::warn!("{}: format {} is newer than this build understands", id, value);
+            }
+            format = Some(value);
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("pinned:") {
+            group.pinned = value.trim() == "true";
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("created:") {
+            group.created_at = value.trim().parse().unwrap_or(group.created_at);
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("updated:") {
+            group.updated_at = value.trim().parse().unwrap_or(group.updated_at);
+            continue;
+        }
+
+        after_text |= indent_width(raw) == 0;
+        in_body = true;
+        push_note(&mut group, &lines, raw);
+    }
+
+    let had_id = lines.iter().map(|l| l.has_id).collect();
+    group.tasks = resolve_hierarchy(lines);
+    (group, had_id)
+}
+
+fn push_note(group: &mut Group, tasks: &[ParsedLine], text: &str) {
+    group.extra.push(NoteLine {
+        after: tasks.last().map(|l| l.task.id.clone()),
+        text: text.to_string(),
+    });
+}
+
+fn render_task(task: &Task, indent: &str, with_meta: bool, lines: &mut Vec<String>) {
+    let checkbox = if task.completed { "[x]" } else { "[ ]" };
+    if !with_meta {
+        lines.push(format!("{}- {} {}", indent, checkbox, task.content));
+        return;
+    }
+    let mut meta = format!("id:{},created:{},order:{}", task.id, task.created_at, task.order);
+    if let Some(time) = &task.scheduled_time {
+        meta.push_str(&format!(",time:{}", time));
+    }
+    if let Some(completed_at) = task.completed_at {
+        meta.push_str(&format!(",completedAt:{}", completed_at));
+    }
+    if let Some(parent) = &task.parent_id {
+        meta.push_str(&format!(",parent:{}", parent));
+    }
+    if task.collapsed {
+        meta.push_str(",collapsed:true");
+    }
+    if let Some(priority) = task.priority.filter(|p| *p != Priority::Default) {
+        meta.push_str(&format!(",priority:{}", priority.as_str()));
+    }
+    if let Some(minutes) = task.estimated_minutes {
+        meta.push_str(&format!(",est:{}", minutes));
+    }
+    if let Some(minutes) = task.actual_minutes {
+        meta.push_str(&format!(",act:{}", minutes));
+    }
+    if !task.intervals.is_empty() {
+        let log: Vec<String> = task
+            .intervals
+            .iter()
+            .map(|i| format!("{}-{}", i.start, i.end))
+            .collect();
+        meta.push_str(&format!(",log:{}", log.join(";")));
+    }
+    for (key, value) in &task.extra {
+        meta.push_str(&format!(",{}:{}", key, value));
+    }
+
+    lines.push(format!("{}- {} {} <!--{}-->", indent, checkbox, task.content, meta));
+}
+
+/// Tasks in the order they are written, with their nesting depth: siblings
+/// by `order`, children under their parent. Tasks whose parent is missing
+/// or stuck in a cycle come out at depth 0.
+pub fn layout(group: &Group) -> Vec<(&Task, usize)> {
+    let ids: HashSet<&str> = group.tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<&Task>> = HashMap::new();
+    let mut roots: Vec<&Task> = Vec::new();
+    for task in &group.tasks {
+        match task.parent_id.as_deref() {
+            Some(parent) if ids.contains(parent) => children.entry(parent).or_default().push(task),
+            _ => roots.push(task),
+        }
+    }
+    roots.sort_by_key(|t| t.order);
+    for list in children.values_mut() {
+        list.sort_by_key(|t| t.order);
+    }
+
+    let mut rendered: HashSet<*const Task> = HashSet::new();
+    let mut out = Vec::with_capacity(group.tasks.len());
+    fn walk<'a>(
+        task: &'a Task,
+        depth: usize,
+        children: &HashMap<&str, Vec<&'a Task>>,
+        rendered: &mut HashSet<*const Task>,
+        out: &mut Vec<(&'a Task, usize)>,
+    ) {
+        if !rendered.insert(task as *const Task) {
+            return;
+        }
+        out.push((task, depth));
+        if let Some(kids) = children.get(task.id.as_str()) {
+            for child in kids {
+                walk(child, depth + 1, children, rendered, out);
+            }
+        }
+    }
+
+    for task in &roots {
+        walk(task, 0, &children, &mut rendered, &mut out);
+    }
+    // Anything left over is stuck in a parent cycle
+    for task in &group.tasks {
+        if !rendered.contains(&(task as *const Task)) {
+            walk(task, 0, &children, &mut rendered, &mut out);
+        }
+    }
+    out
+}
+
+fn serialize(group: &Group, with_meta: bool) -> String {
+    let mut lines = vec![
+        format!("# {}", group.name),
+        String::new(),
+        format!("format: {}", FORMAT_VERSION),
+        format!("pinned: {}", group.pinned),
+        format!("created: {}", group.created_at),
+        format!("updated: {}", group.updated_at),
+    ];
+    // Notes go back after their task; those whose task is gone move to the top
+    let ids: HashSet<&str> = group.tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut notes: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut body = Vec::new();
+    for note in &group.extra {
+        match note.after.as_deref().filter(|id| ids.contains(id)) {
+            Some(id) => notes.entry(id).or_default().push(&note.text),
+            None => body.push(note.text.clone()),
+        }
+    }
+    for (task, depth) in layout(group) {
+        render_task(task, &"  ".repeat(depth), with_meta, &mut body);
+        if let Some(notes) = notes.remove(task.id.as_str()) {
+            body.extend(notes.into_iter().map(str::to_string));
+        }
+    }
+    if !body.is_empty() {
+        lines.push(String::new());
+        lines.append(&mut body);
+    }
+    let ending = group.line_ending.as_str();
+    let mut text = lines.join(ending);
+    if group.final_newline {
+        text.push_str(ending);
+    }
+    text
+}
+
+/// Serializes a group back to Markdown. Children are nested under their
+/// parent by indentation; tasks whose parent is missing (or that sit in a
+/// parent cycle) are written at the top level with their `parent:` intact
+/// so nothing is dropped.
+pub fn serialize_group(group: &Group) -> String {
+    serialize(group, true)
+}
+
+/// The same file without the metadata comments, for clean Markdown mode.
+/// Ids and timestamps have to be saved in the index alongside.
+pub fn serialize_group_clean(group: &Group) -> String {
+    serialize(group, false)
+}
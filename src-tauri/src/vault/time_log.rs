@@ -0,0 +1,319 @@
+// Time tracker file: `time-tracker/time-log.md`
+//
+// # 时间记录
+//
+// ## 2024-11-30
+//
+// ### 应用使用时间
+// - Firefox: 3600秒
+//
+// ### 网站访问时间
+// - github.com: 1200秒
+//
+// ### 番茄钟
+// - 完成: 4
+
+use std::collections::BTreeMap;
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+pub const APPS_SECTION: &str = "应用使用时间";
+pub const WEBSITES_SECTION: &str = "网站访问时间";
+pub const POMODORO_SECTION: &str = "番茄钟";
+const POMODORO_DONE: &str = "完成";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppUsage {
+    pub name: String,
+    /// Seconds
+    pub duration: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayTimeData {
+    pub date: String,
+    pub apps: Vec<AppUsage>,
+    pub websites: Vec<AppUsage>,
+    /// Pomodoros finished that day.
+    #[serde(default)]
+    pub pomodoros: u32,
+}
+
+impl DayTimeData {
+    fn is_empty(&self) -> bool {
+        self.apps.is_empty() && self.websites.is_empty() && self.pomodoros == 0
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Apps,
+    Websites,
+    Pomodoros,
+}
+
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter()
+            .enumerate()
+            .all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+}
+
+// `- Name: 1234秒` (the unit suffix is optional)
+fn parse_usage(line: &str) -> Option<AppUsage> {
+    let rest = line.strip_prefix("- ")?;
+    let (name, value) = rest.rsplit_once(':')?;
+    let value = value.trim();
+    let value = value.strip_suffix('秒').unwrap_or(value);
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(AppUsage {
+        name: name.to_string(),
+        duration: value.parse().ok()?,
+    })
+}
+
+/// Parses the time log. Days with no entries are dropped, as in the UI.
+pub fn parse_time_log(content: &str) -> Vec<DayTimeData> {
+    let mut days: Vec<DayTimeData> = Vec::new();
+    let mut current: Option<DayTimeData> = None;
+    let mut section: Option<Section> = None;
+
+    fn flush(day: Option<DayTimeData>, days: &mut Vec<DayTimeData>) {
+        if let Some(day) = day {
+            if !day.is_empty() {
+                days.push(day);
+            }
+        }
+    }
+
+    for raw in content.lines() {
+        let line = raw.trim();
+
+        if let Some(heading) = line.strip_prefix("## ") {
+            flush(current.take(), &mut days);
+            section = None;
+            let heading = heading.trim();
+            if is_date(heading) {
+                current = Some(DayTimeData {
+                    date: heading.to_string(),
+                    apps: Vec::new(),
+                    websites: Vec::new(),
+                    pomodoros: 0,
+                });
+            }
+            continue;
+        }
+
+        if line.contains(APPS_SECTION) {
+            section = Some(Section::Apps);
+            continue;
+        }
+        if line.contains(WEBSITES_SECTION) {
+            section = Some(Section::Websites);
+            continue;
+        }
+        if line.contains(POMODORO_SECTION) {
+            section = Some(Section::Pomodoros);
+            continue;
+        }
+
+        let (Some(day), Some(section)) = (current.as_mut(), section) else {
+            continue;
+        };
+        if let Some(usage) = parse_usage(line) {
+            match section {
+                Section::Apps => day.apps.push(usage),
+                Section::Websites => day.websites.push(usage),
+                Section::Pomodoros if usage.name == POMODORO_DONE => {
+                    day.pomodoros = day
+                        .pomodoros
+                        .saturating_add(u32::try_from(usage.duration).unwrap_or(u32::MAX))
+                }
+                Section::Pomodoros => {}
+            }
+        }
+    }
+    flush(current.take(), &mut days);
+
+    days
+}
+
+pub fn serialize_time_log(days: &[DayTimeData]) -> String {
+    let mut lines = vec!["# 时间记录".to_string(), String::new()];
+
+    for day in days {
+        lines.push(format!("## {}", day.date));
+        lines.push(String::new());
+        if !day.apps.is_empty() {
+            lines.push(format!("### {}", APPS_SECTION));
+            for app in &day.apps {
+                lines.push(format!("- {}: {}秒", app.name, app.duration));
+            }
+            lines.push(String::new());
+        }
+        if !day.websites.is_empty() {
+            lines.push(format!("### {}", WEBSITES_SECTION));
+            for site in &day.websites {
+                lines.push(format!("- {}: {}秒", site.name, site.duration));
+            }
+            lines.push(String::new());
+        }
+        if day.pomodoros > 0 {
+            lines.push(format!("### {}", POMODORO_SECTION));
+            lines.push(format!("- {}: {}", POMODORO_DONE, day.pomodoros));
+            lines.push(String::new());
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// `YYYY-MM-DD` of a timestamp in local time, the day time spent counts for.
+pub fn local_day(millis: i64) -> String {
+    Local
+        .timestamp_millis_opt(millis)
+        .earliest()
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+// The entry for `date`, created in date order if the log doesn't have it yet
+fn day_mut<'a>(days: &'a mut Vec<DayTimeData>, date: &str) -> &'a mut DayTimeData {
+    let index = match days.iter().position(|d| d.date == date) {
+        Some(index) => index,
+        None => {
+            let index = days.iter().position(|d| d.date.as_str() > date).unwrap_or(days.len());
+            days.insert(
+                index,
+                DayTimeData {
+                    date: date.to_string(),
+                    apps: Vec::new(),
+                    websites: Vec::new(),
+                    pomodoros: 0,
+                },
+            );
+            index
+        }
+    };
+    &mut days[index]
+}
+
+/// Adds `count` finished pomodoros to `date`.
+pub fn add_pomodoros(days: &mut Vec<DayTimeData>, date: &str, count: u32) {
+    let day = day_mut(days, date);
+    day.pomodoros = day.pomodoros.saturating_add(count);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    Apps,
+    Websites,
+}
+
+/// Time per name per day, collected in memory between writes to the log.
+/// Kept in milliseconds so short samples add up; only whole seconds are
+/// written and the rest waits for the next flush.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    days: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl Tally {
+    pub fn add(&mut self, date: &str, name: &str, millis: u64) {
+        if millis == 0 || name.is_empty() {
+            return;
+        }
+        let spent = self
+            .days
+            .entry(date.to_string())
+            .or_default()
+            .entry(name.to_string())
+            .or_default();
+        *spent += millis;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    /// Moves the whole seconds into `days`, adding to entries already there.
+    /// Returns whether anything was added.
+    pub fn drain_into(&mut self, days: &mut Vec<DayTimeData>, kind: UsageKind) -> bool {
+        let mut changed = false;
+        for (date, names) in self.days.iter_mut() {
+            for (name, millis) in names.iter_mut() {
+                let seconds = *millis / 1000;
+                if seconds == 0 {
+                    continue;
+                }
+                *millis %= 1000;
+                changed = true;
+                let day = day_mut(days, date);
+                let list = match kind {
+                    UsageKind::Apps => &mut day.apps,
+                    UsageKind::Websites => &mut day.websites,
+                };
+                match list.iter_mut().find(|u| u.name == *name) {
+                    Some(usage) => usage.duration += seconds,
+                    None => list.push(AppUsage {
+                        name: name.clone(),
+                        duration: seconds,
+                    }),
+                }
+            }
+        }
+        self.days.retain(|_, names| {
+            names.retain(|_, millis| *millis > 0);
+            !names.is_empty()
+        });
+        changed
+    }
+}
+
+/// Turns reports of what is in use into a `Tally`: the time between two
+/// reports goes to whatever the first one named. A gap longer than
+/// `max_gap` means the reports stopped (the machine slept, the browser
+/// closed) and counts for nothing.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    tally: Tally,
+    max_gap: i64,
+    last: Option<(String, i64)>,
+}
+
+impl Sampler {
+    pub fn new(max_gap: i64) -> Self {
+        Sampler {
+            tally: Tally::default(),
+            max_gap,
+            last: None,
+        }
+    }
+
+    /// Credits the time since the last report and notes `current`, `None`
+    /// when nothing is in use.
+    pub fn report(&mut self, now: i64, current: Option<String>) {
+        if let Some((name, at)) = self.last.take() {
+            let spent = now - at;
+            if (0..=self.max_gap).contains(&spent) {
+                self.tally.add(&local_day(at), &name, spent as u64);
+            }
+        }
+        self.last = current.map(|name| (name, now));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tally.is_empty()
+    }
+
+    pub fn drain_into(&mut self, days: &mut Vec<DayTimeData>, kind: UsageKind) -> bool {
+        self.tally.drain_into(days, kind)
+    }
+}
@@ -0,0 +1,361 @@
+// Running task timers, kept in `.nekotick/timers.json` so a restart or a
+// reloaded webview picks them up where they were. A timer only remembers
+// when it started; stopping it turns that into an `Interval` in its task's
+// `log:`. The pomodoro cycle is kept here too, see `pomodoro.rs`, and so
+// is an idle break: timers paused while the user was away, waiting for them
+// to say what the time was.
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use super::atomic;
+use super::error::{NekoError, Result};
+use super::paths::DataPaths;
+use super::pomodoro::{Phase, Pomodoro, PomodoroConfig, Session};
+use super::store;
+use super::tasks::{Group, Interval};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningTimer {
+    pub task_id: String,
+    pub started_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Timers {
+    pub running: Vec<RunningTimer>,
+    pub pomodoro: Pomodoro,
+    pub idle: Option<IdleBreak>,
+}
+
+/// Timers stopped because the user went idle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleBreak {
+    /// When the idleness began; the stopped sessions end here.
+    pub since: i64,
+    /// When the user came back, unset while they're still away.
+    pub returned_at: Option<i64>,
+    pub tasks: Vec<String>,
+    /// The pomodoro that was being worked, paused until the break is settled.
+    #[serde(default)]
+    pub pomodoro: Option<Session>,
+}
+
+/// What to do with the time an idle break took.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum IdleChoice {
+    /// Log it on the paused tasks as if the user had never left.
+    Keep,
+    Discard,
+    /// Log it on another task.
+    Reassign {
+        #[serde(rename = "taskId")]
+        task_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleConfig {
+    /// Idle this long pauses running timers; 0 never does.
+    pub minutes: u32,
+    /// Resume on return and drop the idle time instead of asking.
+    pub auto_pause: bool,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        IdleConfig {
+            minutes: 5,
+            auto_pause: false,
+        }
+    }
+}
+
+/// What a change to the timers left to record: sessions to log into their
+/// tasks and the end times of the pomodoros it finished.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    pub sessions: Vec<(String, Interval)>,
+    pub pomodoros: Vec<i64>,
+}
+
+impl From<Vec<(String, Interval)>> for Outcome {
+    fn from(sessions: Vec<(String, Interval)>) -> Self {
+        Outcome {
+            sessions,
+            pomodoros: Vec::new(),
+        }
+    }
+}
+
+impl Timers {
+    pub fn is_running(&self, task_id: &str) -> bool {
+        self.running.iter().any(|t| t.task_id == task_id)
+    }
+
+    /// Starts timing `task_id` at `now`. Unless `concurrent`, the other
+    /// timers are stopped first; their sessions are returned for logging.
+    /// Starting a running timer changes nothing.
+    pub fn start(&mut self, task_id: &str, now: i64, concurrent: bool) -> Vec<(String, Interval)> {
+        if self.is_running(task_id) {
+            return Vec::new();
+        }
+        let stopped = if concurrent { Vec::new() } else { self.stop_all(now) };
+        self.running.push(RunningTimer {
+            task_id: task_id.to_string(),
+            started_at: now,
+        });
+        stopped
+    }
+
+    /// Stops `task_id`'s timer and returns the session it timed. A pomodoro
+    /// timing the task is abandoned with it.
+    pub fn stop(&mut self, task_id: &str, now: i64) -> Option<Interval> {
+        let index = self.running.iter().position(|t| t.task_id == task_id)?;
+        if self.pomodoro.working_on() == Some(task_id) {
+            self.pomodoro.session = None;
+        }
+        let timer = self.running.remove(index);
+        Some(Interval {
+            start: timer.started_at,
+            end: now.max(timer.started_at),
+        })
+    }
+
+    pub fn stop_all(&mut self, now: i64) -> Vec<(String, Interval)> {
+        let ids: Vec<String> = self.running.iter().map(|t| t.task_id.clone()).collect();
+        ids.into_iter()
+            .filter_map(|id| self.stop(&id, now).map(|interval| (id, interval)))
+            .collect()
+    }
+
+    /// Starts a pomodoro on `task_id`, or on nothing, with `config`'s
+    /// lengths. The task's timer starts like any other, stopping the rest.
+    pub fn start_pomodoro(&mut self, task_id: Option<&str>, now: i64, config: PomodoroConfig) -> Outcome {
+        let stopped = match task_id {
+            Some(id) => self.start(id, now, false),
+            None => Vec::new(),
+        };
+        self.pomodoro.config = config;
+        self.pomodoro.begin(Phase::Work, task_id.map(str::to_string), now);
+        stopped.into()
+    }
+
+    /// Abandons the current work session or break. Time worked so far stays
+    /// on the task but no pomodoro is counted.
+    pub fn stop_pomodoro(&mut self, now: i64) -> Outcome {
+        let mut outcome = Outcome::default();
+        if let Some(id) = self.pomodoro.working_on().map(str::to_string) {
+            if let Some(interval) = self.stop(&id, now) {
+                outcome.sessions.push((id, interval));
+            }
+        }
+        self.pomodoro.session = None;
+        outcome
+    }
+
+    /// Moves the cycle on to `now`. A finished work session counts a
+    /// pomodoro and stops its task's timer at the session's end, not at
+    /// `now`, so time the app spent closed isn't credited. Breaks end the
+    /// cycle; the next pomodoro is started by hand.
+    pub fn advance(&mut self, now: i64) -> Outcome {
+        let mut outcome = Outcome::default();
+        while let Some(session) = self.pomodoro.session.clone().filter(|s| s.ends_at <= now) {
+            if session.phase != Phase::Work {
+                self.pomodoro.session = None;
+                if session.phase == Phase::LongBreak {
+                    self.pomodoro.streak = 0;
+                }
+                break;
+            }
+            self.pomodoro.streak += 1;
+            outcome.pomodoros.push(session.ends_at);
+            let next = self.pomodoro.next_break();
+            // The break begins before the timer stops so stopping doesn't abandon it
+            self.pomodoro.begin(next, session.task_id.clone(), session.ends_at);
+            if let Some(id) = session.task_id {
+                if let Some(interval) = self.stop(&id, session.ends_at) {
+                    outcome.sessions.push((id, interval));
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Stops every timer at `since`, when the user went idle, and remembers
+    /// them for `resolve_idle`. A pomodoro being worked is set aside with
+    /// them rather than abandoned. Nothing happens with no timer running or
+    /// while an earlier break is unresolved.
+    pub fn go_idle(&mut self, since: i64) -> Outcome {
+        if self.running.is_empty() || self.idle.is_some() {
+            return Outcome::default();
+        }
+        let tasks = self.running.iter().map(|t| t.task_id.clone()).collect();
+        // Taken before stopping, so stopping its task doesn't abandon it
+        let pomodoro = match self.pomodoro.working_on() {
+            Some(_) => self.pomodoro.session.take(),
+            None => None,
+        };
+        let mut stopped = self.stop_all(since);
+        // Timers started after the last input have nothing to log
+        stopped.retain(|(_, interval)| interval.millis() > 0);
+        self.idle = Some(IdleBreak {
+            since,
+            returned_at: None,
+            tasks,
+            pomodoro,
+        });
+        stopped.into()
+    }
+
+    /// Notes that the user is back; returns whether a break was waiting.
+    pub fn come_back(&mut self, now: i64) -> bool {
+        match self.idle.as_mut() {
+            Some(idle) if idle.returned_at.is_none() => {
+                idle.returned_at = Some(now.max(idle.since));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Settles the idle break: logs its time as `choice` says and restarts
+    /// the paused timers from when the user came back (or `now`). A paused
+    /// pomodoro resumes too; unless the time away is kept as work, its clock
+    /// stood still meanwhile.
+    pub fn resolve_idle(&mut self, choice: IdleChoice, now: i64) -> Outcome {
+        let Some(idle) = self.idle.take() else {
+            return Outcome::default();
+        };
+        let back = idle.returned_at.unwrap_or(now).max(idle.since);
+        let away = Interval {
+            start: idle.since,
+            end: back,
+        };
+        let kept = choice == IdleChoice::Keep;
+        let sessions = match choice {
+            IdleChoice::Keep => idle.tasks.iter().map(|id| (id.clone(), away)).collect(),
+            IdleChoice::Discard => Vec::new(),
+            IdleChoice::Reassign { task_id } => vec![(task_id, away)],
+        };
+        if let Some(mut session) = idle.pomodoro {
+            // One started by hand while away takes precedence
+            if self.pomodoro.session.is_none() {
+                if !kept {
+                    session.started_at += away.millis();
+                    session.ends_at += away.millis();
+                }
+                self.pomodoro.session = Some(session);
+            }
+        }
+        for id in &idle.tasks {
+            self.start(id, back, true);
+        }
+        sessions.into()
+    }
+
+    /// Whether a pomodoro phase is over and `advance` has work to do.
+    pub fn is_due(&self, now: i64) -> bool {
+        self.pomodoro.session.as_ref().is_some_and(|s| s.ends_at <= now)
+    }
+}
+
+/// Appends a session to the task's log and returns the index of its group.
+/// A session with the same start replaces the logged one: after a crash
+/// between logging a stop and forgetting the timer, the timer resumes and
+/// its next stop logs a longer copy of the same session.
+pub fn log_interval(groups: &mut [Group], task_id: &str, interval: Interval) -> Result<usize> {
+    let (g, t) = locate(groups, task_id)?;
+    let task = &mut groups[g].tasks[t];
+    match task.intervals.iter_mut().find(|i| i.start == interval.start) {
+        Some(logged) => logged.end = logged.end.max(interval.end),
+        None => {
+            task.intervals.push(interval);
+            task.intervals.sort();
+        }
+    }
+    Ok(g)
+}
+
+/// Logs each session into its task and returns the groups that changed,
+/// reading only the groups that own the tasks. `owners` maps task ids to the
+/// group they were last found in and is kept up to date. A task deleted
+/// while its timer ran has nowhere to log to, so its session is dropped.
+pub fn log_sessions(
+    paths: &DataPaths,
+    sessions: &[(String, Interval)],
+    owners: &mut HashMap<String, String>,
+) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (task_id, interval) in sessions {
+        if locate(&groups, task_id).is_err() {
+            match load_owner(paths, task_id, owners.get(task_id).map(String::as_str)) {
+                Ok(group) => {
+                    owners.insert(task_id.clone(), group.id.clone());
+                    groups.push(group);
+                }
+                Err(e) => {
+                    log::warn!("dropping a session of {}: {}", task_id, e);
+                    continue;
+                }
+            }
+        }
+        let _ = log_interval(&mut groups, task_id, *interval);
+    }
+    groups
+}
+
+/// Loads the group holding `task_id`: the `hint` group if it still has the
+/// task, else the first other group that does, reading one file at a time.
+pub fn load_owner(paths: &DataPaths, task_id: &str, hint: Option<&str>) -> Result<Group> {
+    let mut ids = store::list_group_ids(paths).map_err(|e| NekoError::io(e, &paths.tasks))?;
+    if let Some(first) = hint.and_then(|h| ids.iter().position(|id| id == h)) {
+        ids[..=first].rotate_right(1);
+    }
+    for id in ids {
+        match store::load_group(paths, &id) {
+            Ok(group) if group.tasks.iter().any(|t| t.id == task_id) => return Ok(group),
+            Ok(_) => {}
+            Err(e) => log::warn!("skipping group {}: {}", id, e),
+        }
+    }
+    Err(NekoError::not_found(format!("no task {:?}", task_id)).with_task(task_id))
+}
+
+/// Missing or unreadable state means nothing is running.
+pub fn load(paths: &DataPaths) -> Timers {
+    let path = paths.timers_file();
+    if paths.ensure_inside(&path).is_err() {
+        return Timers::default();
+    }
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(paths: &DataPaths, timers: &Timers) -> io::Result<()> {
+    let path = paths.timers_file();
+    paths.ensure_inside(&path)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    atomic::write_atomic(&path, &serde_json::to_vec_pretty(timers)?)
+}
+
+/// `(group index, task index)` of the task with exactly this id. Timers
+/// never match by prefix: a deleted task's time must not land elsewhere.
+pub fn locate(groups: &[Group], task_id: &str) -> Result<(usize, usize)> {
+    groups
+        .iter()
+        .enumerate()
+        .find_map(|(g, group)| group.tasks.iter().position(|t| t.id == task_id).map(|t| (g, t)))
+        .ok_or_else(|| NekoError::not_found(format!("no task {:?}", task_id)).with_task(task_id))
+}
@@ -0,0 +1,151 @@
+// Watches `tasks/` for edits made outside the app (editors, Syncthing) and
+// pushes the re-parsed group to the main window.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::{self, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::storage::GroupBases;
+use crate::vault::{self, atomic, DataPaths, Group};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+pub const GROUP_CHANGED: &str = "group-changed";
+pub const GROUP_REMOVED: &str = "group-removed";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupChanged {
+    pub group: Group,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupRemoved {
+    pub id: String,
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Remembers the last content we wrote (or already reported) for each file
+/// so our own saves don't come back as external edits.
+#[derive(Default)]
+pub struct WriteTracker {
+    known: Mutex<HashMap<PathBuf, u64>>,
+}
+
+impl WriteTracker {
+    /// Call before writing `contents` to `path`.
+    pub fn record(&self, path: &Path, contents: &[u8]) {
+        if let Ok(mut known) = self.known.lock() {
+            known.insert(path.to_path_buf(), hash_bytes(contents));
+        }
+    }
+
+    // True if `contents` differs from what we last knew, remembering it
+    fn observe(&self, path: &Path, contents: &[u8]) -> bool {
+        let hash = hash_bytes(contents);
+        match self.known.lock() {
+            Ok(mut known) => known.insert(path.to_path_buf(), hash) != Some(hash),
+            Err(_) => true,
+        }
+    }
+
+    fn forget(&self, path: &Path) {
+        if let Ok(mut known) = self.known.lock() {
+            known.remove(path);
+        }
+    }
+}
+
+/// Keeps the debouncer (and with it the OS watch) alive for the app's lifetime.
+pub struct VaultWatcher {
+    _debouncer: Mutex<Debouncer<RecommendedWatcher>>,
+}
+
+// `tasks/<id>.md` -> `<id>`, ignoring temp files, backups and subfolders
+fn group_id_for(paths: &DataPaths, path: &Path) -> Option<String> {
+    if path.parent() != Some(paths.tasks.as_path()) || atomic::is_temp_file(path) {
+        return None;
+    }
+    let id = path.file_name()?.to_str()?.strip_suffix(".md")?;
+    vault::store::is_valid_group_id(id).then(|| id.to_string())
+}
+
+// Shared state the watcher thread needs
+pub struct WatchContext {
+    pub paths: DataPaths,
+    pub tracker: Arc<WriteTracker>,
+    pub bases: Arc<GroupBases>,
+}
+
+fn handle_path(app: &AppHandle, ctx: &WatchContext, path: &Path) {
+    let WatchContext { paths, tracker, bases } = ctx;
+    let Some(id) = group_id_for(paths, path) else {
+        return;
+    };
+    // A group file symlinked out of the vault is never read
+    if paths.ensure_inside(path).is_err() && path.exists() {
+        return;
+    }
+
+    let Ok(bytes) = std::fs::read(path) else {
+        if !path.exists() {
+            tracker.forget(path);
+            bases.forget(&id);
+            let _ = app.emit_to("main", GROUP_REMOVED, GroupRemoved { id });
+        }
+        return;
+    };
+    if !tracker.observe(path, &bytes) {
+        return;
+    }
+
+    match vault::store::load_group(paths, &id) {
+        // Not the merge base yet: a save of the frontend's older copy may
+        // already be on its way. The frontend calls `adopt_group` once it
+        // has taken this version over.
+        Ok(group) => {
+            let _ = app.emit_to("main", GROUP_CHANGED, GroupChanged { group });
+        }
+        Err(e) => log::warn!("failed to reload group {}: {}", id, e),
+    }
+}
+
+/// Starts watching the vault's `tasks/` folder.
+pub fn start(app: &AppHandle, ctx: WatchContext) -> notify::Result<VaultWatcher> {
+    let app = app.clone();
+    let tasks_dir = ctx.paths.tasks.clone();
+
+    let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                log::warn!("vault watcher error: {}", e);
+                return;
+            }
+        };
+        let mut changed: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+        changed.sort();
+        changed.dedup();
+        for path in changed {
+            handle_path(&app, &ctx, &path);
+        }
+    })?;
+    debouncer.watcher().watch(&tasks_dir, RecursiveMode::NonRecursive)?;
+
+    Ok(VaultWatcher {
+        _debouncer: Mutex::new(debouncer),
+    })
+}
@@ -0,0 +1,320 @@
+// Website time from the browser extension in `browser-extension/`. Off
+// unless `trackWebsites` is set; then a listener on 127.0.0.1, at a port the
+// OS picks, takes heartbeats naming the active tab's domain. The port and a
+// fresh token go to `.nekotick/extension.json` for the extension's options.
+// Requests without the token, or addressed to any host but this one, are
+// refused. There are no CORS headers: the extension's host permission gets
+// it past them, and a web page can't send the token header without a
+// preflight nobody answers. Time is kept in memory and lands in the
+// `网站访问时间` section of `time-log.md` every half minute and when
+// tracking stops.
+//
+//   POST /heartbeat
+//   Authorization: Bearer <token>
+//
+//   {"domain": "github.com"}   (null when no browser tab is in front)
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::vault::time_log::{Sampler, UsageKind};
+use crate::vault::{self, atomic, DataPaths, Settings};
+
+// The extension beats every 30 seconds; two missed beats end the visit
+const MAX_GAP: i64 = 65_000;
+const POLL: Duration = Duration::from_millis(200);
+// Each connection gets a thread; one that stalls only holds up itself
+const TIMEOUT: Duration = Duration::from_secs(2);
+const FLUSH_EVERY: Duration = Duration::from_secs(30);
+const MAX_HEAD: u64 = 8 * 1024;
+const MAX_BODY: usize = 4 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+struct Heartbeat {
+    domain: Option<String>,
+}
+
+struct Listening {
+    endpoint: Endpoint,
+    stop: Arc<AtomicBool>,
+}
+
+pub struct WebTracker {
+    listening: Mutex<Option<Listening>>,
+    sampler: Arc<Mutex<Sampler>>,
+}
+
+impl Default for WebTracker {
+    fn default() -> Self {
+        WebTracker {
+            listening: Mutex::new(None),
+            sampler: Arc::new(Mutex::new(Sampler::new(MAX_GAP))),
+        }
+    }
+}
+
+impl WebTracker {
+    /// Where the listener is, `None` while website tracking is off.
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        self.listening().as_ref().map(|l| l.endpoint.clone())
+    }
+
+    /// Starts or stops listening to match `settings`.
+    pub fn apply(&self, paths: &DataPaths, settings: &Settings) -> io::Result<()> {
+        if settings.track_websites {
+            self.start(paths).map(|_| ())
+        } else {
+            self.stop(paths)
+        }
+    }
+
+    /// Listens on a new port with a new token; already listening, keeps both.
+    pub fn start(&self, paths: &DataPaths) -> io::Result<Endpoint> {
+        let mut listening = self.listening();
+        if let Some(current) = listening.as_ref() {
+            return Ok(current.endpoint.clone());
+        }
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        // Polled, so turning tracking off can close the port
+        listener.set_nonblocking(true)?;
+        let endpoint = Endpoint {
+            port: listener.local_addr()?.port(),
+            token: new_token()?,
+        };
+
+        let path = paths.extension_file();
+        paths.ensure_inside(&path)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        atomic::write_atomic(&path, &serde_json::to_vec_pretty(&endpoint)?)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let server = Arc::new(Server {
+            endpoint: endpoint.clone(),
+            sampler: self.sampler.clone(),
+            paths: paths.clone(),
+        });
+        let stopped = stop.clone();
+        thread::Builder::new()
+            .name("nekotick-web".into())
+            .spawn(move || server.run(listener, &stopped))?;
+        log::info!("listening for the browser extension on 127.0.0.1:{}", endpoint.port);
+        *listening = Some(Listening {
+            endpoint: endpoint.clone(),
+            stop,
+        });
+        Ok(endpoint)
+    }
+
+    /// Closes the port, writes out the time collected and forgets the token.
+    pub fn stop(&self, paths: &DataPaths) -> io::Result<()> {
+        if let Some(listening) = self.listening().take() {
+            listening.stop.store(true, Ordering::Relaxed);
+            log::info!("stopped listening for the browser extension");
+        }
+        flush(&self.sampler, paths);
+        match fs::remove_file(paths.extension_file()) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn listening(&self) -> MutexGuard<'_, Option<Listening>> {
+        self.listening.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// 128 bits from the OS, in hex
+fn new_token() -> io::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn lock(sampler: &Mutex<Sampler>) -> MutexGuard<'_, Sampler> {
+    sampler.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Adds the whole seconds collected so far to the log
+fn flush(sampler: &Mutex<Sampler>, paths: &DataPaths) {
+    let mut sampler = lock(sampler);
+    if sampler.is_empty() {
+        return;
+    }
+    if let Err(e) = vault::store::update_time_log(paths, |days| sampler.drain_into(days, UsageKind::Websites)) {
+        log::warn!("failed to write website time: {}", e);
+    }
+}
+
+/// A domain as it goes into the log, `None` if it isn't one. Browsers give
+/// international names in punycode, so ASCII is all there is.
+pub fn domain(raw: &str) -> Option<String> {
+    let domain = raw.trim().trim_end_matches('.').to_ascii_lowercase();
+    let valid = !domain.is_empty()
+        && domain.len() <= 253
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'));
+    valid.then_some(domain)
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+    let mut reader = BufReader::new(stream.take(MAX_HEAD + MAX_BODY as u64));
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(invalid("bad request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut headers = Vec::new();
+    let mut head = line.len() as u64;
+    loop {
+        line.clear();
+        head += reader.read_line(&mut line)? as u64;
+        if head > MAX_HEAD {
+            return Err(invalid("headers too long"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':').ok_or_else(|| invalid("bad header"))?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+
+    let mut request = Request {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+    let length: usize = match request.header("content-length") {
+        Some(value) => value.parse().map_err(|_| invalid("bad content length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(invalid("body too long"));
+    }
+    request.body.resize(length, 0);
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+struct Server {
+    endpoint: Endpoint,
+    sampler: Arc<Mutex<Sampler>>,
+    paths: DataPaths,
+}
+
+impl Server {
+    fn run(self: Arc<Self>, listener: TcpListener, stop: &AtomicBool) {
+        let mut flushed = Instant::now();
+        while !stop.load(Ordering::Relaxed) {
+            if flushed.elapsed() >= FLUSH_EVERY {
+                flush(&self.sampler, &self.paths);
+                flushed = Instant::now();
+            }
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let server = self.clone();
+                    let spawned = thread::Builder::new().name("nekotick-web-conn".into()).spawn(move || {
+                        if let Err(e) = server.handle(stream) {
+                            log::debug!("browser extension request failed: {}", e);
+                        }
+                    });
+                    if let Err(e) = spawned {
+                        log::warn!("browser extension listener: {}", e);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL),
+                Err(e) => {
+                    log::warn!("browser extension listener: {}", e);
+                    thread::sleep(POLL);
+                }
+            }
+        }
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        // Some platforms hand out accepted sockets non-blocking like the listener
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        let (status, reason) = match read_request(&stream) {
+            Ok(request) => self.respond(&request),
+            Err(_) => (400, "Bad Request"),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\n\
+             Content-Length: 0\r\n\
+             Connection: close\r\n\r\n",
+            status, reason
+        )?;
+        stream.flush()
+    }
+
+    fn respond(&self, request: &Request) -> (u16, &'static str) {
+        // Anything but our own address is a page using DNS rebinding
+        let port = self.endpoint.port;
+        let host = request.header("host").unwrap_or_default();
+        if host != format!("127.0.0.1:{}", port) && host != format!("localhost:{}", port) {
+            return (403, "Forbidden");
+        }
+        if request.path != "/heartbeat" {
+            return (404, "Not Found");
+        }
+        if request.method != "POST" {
+            return (405, "Method Not Allowed");
+        }
+        if request.header("authorization") != Some(&format!("Bearer {}", self.endpoint.token)) {
+            return (401, "Unauthorized");
+        }
+        let Ok(beat) = serde_json::from_slice::<Heartbeat>(&request.body) else {
+            return (400, "Bad Request");
+        };
+        let current = match beat.domain.as_deref().map(domain) {
+            Some(None) => return (400, "Bad Request"),
+            Some(domain) => domain,
+            None => None,
+        };
+
+        lock(&self.sampler).report(vault::now_millis(), current);
+        (204, "No Content")
+    }
+}
+
+#[tauri::command]
+pub fn web_endpoint(web: State<'_, Arc<WebTracker>>) -> Option<Endpoint> {
+    web.endpoint()
+}
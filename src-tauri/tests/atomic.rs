@@ -0,0 +1,84 @@
+mod common;
+
+use std::fs;
+use std::io;
+use std::thread;
+
+use common::TempDir;
+use nekotick_lib::vault::atomic::{backup_path, read_with_fallback, write_atomic, write_with_backups, Source};
+
+fn good(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"# ")
+}
+
+#[test]
+fn healthy_versions_rotate_through_the_backups() {
+    let dir = TempDir::new("backups");
+    let path = dir.path().join("inbox.md");
+    for version in ["# 1", "# 2", "# 2", "# 3", "damaged", "# 4", "# 5"] {
+        write_with_backups(&path, version.as_bytes(), 3, good).unwrap();
+    }
+
+    let read = |generation| fs::read_to_string(backup_path(&path, generation)).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "# 5");
+    // Rewriting the same content and the damaged version left no generation
+    assert_eq!([read(1), read(2), read(3)], ["# 4", "# 3", "# 2"]);
+    assert!(!backup_path(&path, 4).exists());
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+}
+
+#[test]
+fn damaged_files_are_read_from_the_newest_good_backup() {
+    let dir = TempDir::new("fallback");
+    let path = dir.path().join("inbox.md");
+    let missing = read_with_fallback(&path, 3, good).unwrap_err();
+    assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+
+    fs::write(&path, "# current").unwrap();
+    assert_eq!(
+        read_with_fallback(&path, 3, good).unwrap(),
+        (b"# current".to_vec(), Source::Primary)
+    );
+
+    fs::write(&path, "truncated").unwrap();
+    fs::write(backup_path(&path, 1), "also bad").unwrap();
+    fs::write(backup_path(&path, 2), "# older").unwrap();
+    fs::write(backup_path(&path, 3), "# oldest").unwrap();
+    assert_eq!(
+        read_with_fallback(&path, 3, good).unwrap(),
+        (b"# older".to_vec(), Source::Backup(2))
+    );
+
+    // With no good copy anywhere the damaged file is still handed back
+    fs::remove_file(backup_path(&path, 2)).unwrap();
+    fs::remove_file(backup_path(&path, 3)).unwrap();
+    assert_eq!(
+        read_with_fallback(&path, 3, good).unwrap(),
+        (b"truncated".to_vec(), Source::Primary)
+    );
+}
+
+#[test]
+fn concurrent_writes_never_mix_their_bytes() {
+    let dir = TempDir::new("concurrent");
+    let path = dir.path().join("inbox.md");
+    let versions: Vec<String> = (0..8)
+        .map(|i| format!("# {}\n", i.to_string().repeat(64 * 1024)))
+        .collect();
+
+    thread::scope(|scope| {
+        for version in &versions {
+            let path = &path;
+            scope.spawn(move || {
+                for _ in 0..10 {
+                    write_atomic(path, version.as_bytes()).unwrap();
+                }
+            });
+        }
+    });
+
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(versions.contains(&written));
+    // Every temp file was renamed away
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,80 @@
+use nekotick_lib::vault::audit::{self, Totals};
+use nekotick_lib::vault::{merge, tasks, Interval};
+
+const WORK: &str = "# 工作
+
+- [ ] Draft #writing <!--id:a,created:1,order:0,est:30,act:5,log:0-600000;1200000-1800000-->
+- [x] Review #writing #team <!--id:b,created:1,order:1,est:15-->
+- [ ] Untimed #writing <!--id:c,created:1,order:2-->";
+
+const HOME: &str = "# 家
+
+- [ ] Dishes #chores <!--id:d,created:1,order:0,act:20-->";
+
+#[test]
+fn fields_round_trip_through_the_file() {
+    let group = tasks::parse_group("work", WORK);
+    let draft = &group.tasks[0];
+    assert_eq!(draft.estimated_minutes, Some(30));
+    assert_eq!(draft.actual_minutes, Some(5));
+    assert_eq!(
+        draft.intervals,
+        [
+            Interval { start: 0, end: 600_000 },
+            Interval {
+                start: 1_200_000,
+                end: 1_800_000
+            }
+        ]
+    );
+    assert_eq!(draft.actual_millis(), 25 * 60_000);
+    assert!(draft.extra.is_empty());
+    assert_eq!(tasks::parse_group("work", &tasks::serialize_group(&group)), group);
+}
+
+#[test]
+fn merge_keeps_sessions_from_both_sides() {
+    let base = tasks::parse_group("work", WORK);
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    ours.tasks[0].intervals.push(Interval {
+        start: 2_000_000,
+        end: 2_100_000,
+    });
+    theirs.tasks[0].intervals.push(Interval {
+        start: 3_000_000,
+        end: 3_100_000,
+    });
+    theirs.tasks[0].estimated_minutes = Some(45);
+
+    let merged = merge::merge_groups(&base, &ours, &theirs);
+    assert!(merged.conflicts.is_empty());
+    assert_eq!(merged.group.tasks[0].intervals.len(), 4);
+    assert_eq!(merged.group.tasks[0].estimated_minutes, Some(45));
+}
+
+#[test]
+fn summary_by_group_and_tag() {
+    let groups = [tasks::parse_group("work", WORK), tasks::parse_group("home", HOME)];
+    let summary = audit::summarize(&groups);
+
+    let totals = |tasks, completed, estimated_minutes, actual_minutes| Totals {
+        tasks,
+        completed,
+        estimated_minutes,
+        actual_minutes,
+    };
+    assert_eq!(summary.total, totals(3, 1, 45, 45));
+    assert_eq!(summary.groups[0].totals, totals(2, 1, 45, 25));
+    assert_eq!(summary.groups[1].name, "家");
+    let tags: Vec<(&str, &Totals)> = summary.tags.iter().map(|t| (t.tag.as_str(), &t.totals)).collect();
+    assert_eq!(
+        tags,
+        [
+            ("chores", &totals(1, 0, 0, 20)),
+            ("team", &totals(1, 1, 15, 0)),
+            ("writing", &totals(2, 1, 45, 25)),
+        ]
+    );
+    assert_eq!(audit::tags("# not a tag #a #a #b, #"), ["a", "b"]);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::fs;
+
+use common::temp_vault;
+use nekotick_lib::vault::{ops, store, tasks, Group};
+
+const INBOX: &str = "# 收集箱
+
+- [ ] a <!-- id:a,created:1,order:0 -->
+  - [ ] a1 <!-- id:a1,created:1,order:0,parent:a -->
+    - [ ] a11 <!-- id:a11,created:1,order:0,parent:a1 -->
+- [ ] b <!-- id:b,created:1,order:1 -->
+- [ ] c <!-- id:c,created:1,order:2 -->";
+
+const WORK: &str = "# 工作
+
+- [ ] w <!-- id:w,created:1,order:0 -->";
+
+fn groups() -> Vec<Group> {
+    vec![tasks::parse_group("inbox", INBOX), tasks::parse_group("work", WORK)]
+}
+
+fn ids(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn top_level(group: &Group) -> Vec<&str> {
+    let mut top: Vec<_> = group.tasks.iter().filter(|t| t.parent_id.is_none()).collect();
+    top.sort_by_key(|t| t.order);
+    top.iter().map(|t| t.id.as_str()).collect()
+}
+
+#[test]
+fn moves_selection_with_descendants_in_order() {
+    let mut groups = groups();
+    let batch = ops::move_tasks(&mut groups, &ids(&["c", "a"]), "work", Some(0)).unwrap();
+
+    assert_eq!(batch.moved, ids(&["c", "a", "a1", "a11"]));
+    assert_eq!(batch.groups, ids(&["work", "inbox"]));
+    assert_eq!(top_level(&groups[0]), ["b"]);
+    assert_eq!(top_level(&groups[1]), ["c", "a", "w"]);
+    // Subtasks keep their parents
+    let a11 = groups[1].tasks.iter().find(|t| t.id == "a11").unwrap();
+    assert_eq!(a11.parent_id.as_deref(), Some("a1"));
+}
+
+#[test]
+fn nested_and_repeated_selections_move_once() {
+    let mut groups = groups();
+    let batch = ops::move_tasks(&mut groups, &ids(&["a1", "a", "a"]), "work", None).unwrap();
+    assert_eq!(batch.moved, ids(&["a", "a1", "a11"]));
+    assert_eq!(top_level(&groups[1]), ["w", "a"]);
+}
+
+#[test]
+fn unknown_id_changes_nothing() {
+    let mut groups = groups();
+    let before = groups.clone();
+    assert!(ops::move_tasks(&mut groups, &ids(&["a", "nope"]), "work", None).is_err());
+    assert!(ops::move_tasks(&mut groups, &ids(&["a"]), "nope", None).is_err());
+    assert_eq!(groups, before);
+}
+
+#[test]
+fn interrupted_write_is_replayed_on_load() {
+    let paths = temp_vault("journal");
+    let mut groups = groups();
+    store::save_groups(&paths, &groups.iter().collect::<Vec<_>>()).unwrap();
+    assert!(!paths.journal_file().exists());
+
+    // Simulate dying after the journal was written but before the files were
+    let batch = ops::move_tasks(&mut groups, &ids(&["b"]), "work", None).unwrap();
+    let files: Vec<(String, String)> = groups
+        .iter()
+        .filter(|g| batch.groups.contains(&g.id))
+        .map(|g| (g.id.clone(), tasks::serialize_group(g)))
+        .collect();
+    fs::write(paths.journal_file(), serde_json::to_vec(&files).unwrap()).unwrap();
+
+    let loaded = store::load_groups(&paths).unwrap();
+    assert!(!paths.journal_file().exists());
+    assert_eq!(top_level(&loaded[0]), ["a", "c"]);
+    assert_eq!(top_level(&loaded[1]), ["w", "b"]);
+}
+
+#[test]
+fn a_failed_batch_never_overwrites_later_saves() {
+    let paths = temp_vault("journal-failure");
+    let mut groups = groups();
+    store::save_groups(&paths, &groups.iter().collect::<Vec<_>>()).unwrap();
+    let inbox = fs::read_to_string(paths.group_file("inbox")).unwrap();
+
+    // `work.md` can't be replaced while a folder sits in its place
+    fs::remove_file(paths.group_file("work")).unwrap();
+    fs::create_dir(paths.group_file("work")).unwrap();
+    ops::move_tasks(&mut groups, &ids(&["b"]), "work", None).unwrap();
+    assert!(store::save_groups(&paths, &groups.iter().collect::<Vec<_>>()).is_err());
+    assert!(!paths.journal_file().exists());
+    // The half of the move that was written is undone
+    assert_eq!(fs::read_to_string(paths.group_file("inbox")).unwrap(), inbox);
+    fs::remove_dir(paths.group_file("work")).unwrap();
+
+    let mut newer = tasks::parse_group("inbox", INBOX);
+    newer.name = "Newer".into();
+    store::save_group(&paths, &newer).unwrap();
+    assert_eq!(store::load_groups(&paths).unwrap()[0].name, "Newer");
+
+    // A journal from a crash lands before the next save, not over it
+    let journaled = vec![("inbox".to_string(), WORK.replace("工作", "Crashed"))];
+    fs::write(paths.journal_file(), serde_json::to_vec(&journaled).unwrap()).unwrap();
+    newer.name = "Newest".into();
+    store::save_group(&paths, &newer).unwrap();
+    assert!(!paths.journal_file().exists());
+    assert_eq!(store::load_groups(&paths).unwrap()[0].name, "Newest");
+}
@@ -0,0 +1,63 @@
+use nekotick_lib::vault::checklist::{file_stem, import_items, parse_checklist, render_checklist, ChecklistItem};
+use nekotick_lib::vault::{tasks, Group};
+
+fn item(depth: usize, completed: bool, content: &str) -> ChecklistItem {
+    ChecklistItem {
+        depth,
+        completed,
+        content: content.to_string(),
+    }
+}
+
+#[test]
+fn parses_any_indent_width() {
+    let text = "# Notes\n\nintro\n- [ ] a\n    - [x] b\n\t\t- [ ] c\n  - [ ] d\n- [ ] e <!-- id:e,created:1,order:0 -->\n- [ ]\n";
+    assert_eq!(
+        parse_checklist(text),
+        vec![
+            item(0, false, "a"),
+            item(1, true, "b"),
+            item(2, false, "c"),
+            item(1, false, "d"),
+            item(0, false, "e"),
+        ]
+    );
+}
+
+#[test]
+fn accepts_star_and_plus_bullets() {
+    let text = "* [ ] a\n\t+ [X] b\n- [ ] c\n";
+    assert_eq!(
+        parse_checklist(text),
+        vec![item(0, false, "a"), item(1, true, "b"), item(0, false, "c")]
+    );
+}
+
+#[test]
+fn import_then_export_keeps_the_tree() {
+    let text = "- [ ] a\n  - [x] b\n    - [ ] c\n  - [ ] d\n- [ ] e\n";
+    let mut group = Group::new("inbox", "收集箱");
+    let ids = import_items(&mut group, &parse_checklist(text));
+    assert_eq!(ids.len(), 5);
+
+    let roots: Vec<String> = group
+        .tasks
+        .iter()
+        .filter(|t| t.parent_id.is_none())
+        .map(|t| t.id.clone())
+        .collect();
+    assert_eq!(render_checklist(&group, &roots), text);
+
+    // And the vault file format keeps it as well
+    let reparsed = tasks::parse_group("inbox", &tasks::serialize_group(&group));
+    assert_eq!(render_checklist(&reparsed, &roots), text);
+}
+
+#[test]
+fn file_stems_are_safe() {
+    assert_eq!(file_stem("Buy milk"), "Buy milk");
+    assert_eq!(file_stem("a/b\\c:d*e?f\"g<h>i|j"), "abcdefghij");
+    assert_eq!(file_stem("../.."), "task");
+    assert_eq!(file_stem("   "), "task");
+    assert_eq!(file_stem(&"长".repeat(100)).chars().count(), 60);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::fs;
+
+use common::temp_vault;
+use nekotick_lib::vault::index::{self, IndexEntry};
+use nekotick_lib::vault::{settings, store, tasks, Group, Settings};
+
+const INBOX: &str = "# 收集箱
+
+- [ ] a <!-- id:a,created:11,order:0,priority:red -->
+  - [x] a1 <!-- id:a1,created:12,order:0,parent:a,completedAt:20 -->
+- [ ] b <!-- id:b,created:13,order:1,time:09:00 -->
+- [ ] same <!-- id:s1,created:14,order:2 -->
+- [ ] same <!-- id:s2,created:15,order:3 -->";
+
+fn inbox() -> (Group, Vec<IndexEntry>) {
+    let group = tasks::parse_group("inbox", INBOX);
+    let entries = index::entries(&group);
+    (group, entries)
+}
+
+fn id_of<'a>(group: &'a Group, content: &str) -> Vec<&'a str> {
+    group
+        .tasks
+        .iter()
+        .filter(|t| t.content == content)
+        .map(|t| t.id.as_str())
+        .collect()
+}
+
+#[test]
+fn clean_file_and_index_restore_the_group() {
+    let (group, entries) = inbox();
+    let clean = tasks::serialize_group_clean(&group);
+    assert!(!clean.contains("<!--"));
+    assert!(clean.contains("\n  - [x] a1\n"));
+
+    let reparsed = index::parse_group("inbox", &clean, &entries);
+    assert_eq!(tasks::serialize_group(&reparsed), tasks::serialize_group(&group));
+}
+
+#[test]
+fn hand_edits_keep_ids_by_hash_then_neighbours() {
+    let (group, entries) = inbox();
+    // Moved `b` to the top, retyped `a`, added a line, kept the duplicates
+    let edited = tasks::serialize_group_clean(&group)
+        .replace("- [ ] b\n", "")
+        .replace("- [ ] a\n", "- [ ] b\n- [ ] a, retyped\n")
+        .replace("- [ ] same\n- [ ] same", "- [ ] same\n- [ ] same\n- [ ] brand new");
+    let reparsed = index::parse_group("inbox", &edited, &entries);
+
+    assert_eq!(id_of(&reparsed, "b"), ["b"]);
+    assert_eq!(id_of(&reparsed, "a1"), ["a1"]);
+    assert_eq!(id_of(&reparsed, "same"), ["s1", "s2"]);
+    assert_eq!(
+        reparsed
+            .tasks
+            .iter()
+            .find(|t| t.id == "b")
+            .unwrap()
+            .scheduled_time
+            .as_deref(),
+        Some("09:00")
+    );
+    // `a, retyped` sits where `a` was written, so it takes `a`'s place
+    let retyped = reparsed.tasks.iter().find(|t| t.content == "a, retyped").unwrap();
+    assert_eq!(retyped.id, "a");
+    assert_eq!(
+        reparsed
+            .tasks
+            .iter()
+            .find(|t| t.id == "a1")
+            .unwrap()
+            .parent_id
+            .as_deref(),
+        Some("a")
+    );
+    let new = id_of(&reparsed, "brand new");
+    assert!(new[0].starts_with("task-"));
+}
+
+#[test]
+fn setting_switches_the_written_format() {
+    let paths = temp_vault("clean-markdown");
+    let (group, _) = inbox();
+
+    let clean = Settings {
+        clean_markdown: true,
+        ..Settings::default()
+    };
+    settings::save(&paths, &clean).unwrap();
+    store::save_groups(&paths, &[&group]).unwrap();
+    assert!(!fs::read_to_string(paths.group_file("inbox")).unwrap().contains("<!--"));
+    assert!(paths.index_file().exists());
+    let loaded = store::load_groups(&paths).unwrap();
+    assert_eq!(tasks::serialize_group(&loaded[0]), tasks::serialize_group(&group));
+
+    // Back to annotated lines: the index entry is dropped
+    settings::save(&paths, &Settings::default()).unwrap();
+    store::save_groups(&paths, &[&loaded[0]]).unwrap();
+    assert_eq!(
+        fs::read_to_string(paths.group_file("inbox")).unwrap(),
+        tasks::serialize_group(&group)
+    );
+    assert!(index::load(&paths).groups.is_empty());
+}
+
+#[test]
+fn a_parent_the_indent_cannot_show_comes_back_from_the_index() {
+    // `orphan` points at a task in no file, `x` and `y` at each other
+    let group = tasks::parse_group(
+        "inbox",
+        "# 收集箱
+
+- [ ] orphan <!-- id:orphan,created:11,order:0,parent:gone -->
+- [ ] x <!-- id:x,created:12,order:1,parent:y -->
+- [ ] y <!-- id:y,created:13,order:2,parent:x -->
+- [ ] free <!-- id:free,created:14,order:3 -->",
+    );
+    let entries = index::entries(&group);
+    let parent_of = |group: &Group, id: &str| {
+        let task = group.tasks.iter().find(|t| t.id == id).unwrap();
+        task.parent_id.clone()
+    };
+
+    let reparsed = index::parse_group("inbox", &tasks::serialize_group_clean(&group), &entries);
+    for id in ["orphan", "x", "y", "free"] {
+        assert_eq!(parent_of(&reparsed, id), parent_of(&group, id), "{}", id);
+    }
+
+    // A line outdented by hand had its parent shown by the indent, so it stays out
+    let (group, entries) = inbox();
+    let edited = tasks::serialize_group_clean(&group).replace("  - [x] a1", "- [x] a1");
+    let reparsed = index::parse_group("inbox", &edited, &entries);
+    assert_eq!(parent_of(&reparsed, "a1"), None);
+}
@@ -0,0 +1,47 @@
+// Runs the `nekotick-cli` binary against a temporary vault.
+mod common;
+
+use std::process::Command;
+
+use common::temp_vault;
+use nekotick_lib::vault::{store, DataPaths, Task};
+
+fn cli(paths: &DataPaths, args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_nekotick-cli"))
+        .arg("--data-dir")
+        .arg(&paths.root)
+        .args(args)
+        .env_remove("NEKOTICK_HOME")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    (
+        out.status.success(),
+        format!("{}{}", String::from_utf8_lossy(&out.stdout), stderr),
+    )
+}
+
+fn contents(paths: &DataPaths) -> Vec<String> {
+    let group = store::load_group(paths, "default").unwrap();
+    group.tasks.iter().map(|t| t.content.clone()).collect()
+}
+
+#[test]
+fn flags_count_only_where_options_are_expected() {
+    let paths = temp_vault("cli-args");
+
+    // After the command `--json` is text, and after `--` so is anything else
+    assert!(cli(&paths, &["add", "fix", "--json", "output"]).0);
+    assert!(cli(&paths, &["add", "-g", "default", "--", "-g", "--priority", "red"]).0);
+    assert_eq!(contents(&paths), ["fix --json output", "-g --priority red"]);
+
+    let (ok, out) = cli(&paths, &["--json", "add", "ship", "--priority", "red"]);
+    assert!(ok, "{}", out);
+    let task: Task = serde_json::from_str(out.trim()).unwrap();
+    assert_eq!(task.content, "ship");
+
+    let (ok, out) = cli(&paths, &["--verbose", "groups"]);
+    assert!(!ok && out.contains("unknown option \"--verbose\""), "{}", out);
+    let (ok, out) = cli(&paths, &["add", "text", "--group"]);
+    assert!(!ok && out.contains("--group needs a value"), "{}", out);
+}
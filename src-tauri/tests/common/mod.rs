@@ -0,0 +1,61 @@
+// Fixtures shared by the integration tests. Each test binary compiles its
+// own copy and uses only some of it.
+#![allow(dead_code)]
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use nekotick_lib::vault::{DataDirSource, DataPaths};
+
+/// A fresh folder under the system temp dir, removed again on drop, so a
+/// failing assertion doesn't leave it behind.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        // Tests in one binary run in parallel and may share a name
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "nekotick-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A vault with its folders created in a `TempDir`.
+pub struct TempVault {
+    paths: DataPaths,
+    _dir: TempDir,
+}
+
+impl Deref for TempVault {
+    type Target = DataPaths;
+
+    fn deref(&self) -> &DataPaths {
+        &self.paths
+    }
+}
+
+pub fn temp_vault(name: &str) -> TempVault {
+    let dir = TempDir::new(name);
+    let paths = DataPaths::new(dir.path(), DataDirSource::Explicit);
+    paths.ensure().unwrap();
+    TempVault { paths, _dir: dir }
+}
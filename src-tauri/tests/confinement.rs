@@ -0,0 +1,62 @@
+mod common;
+
+use std::fs;
+use std::io;
+
+use common::{temp_vault, TempDir};
+use nekotick_lib::vault::settings::Settings;
+use nekotick_lib::vault::store;
+
+#[test]
+fn paths_inside_the_root_are_allowed() {
+    let paths = temp_vault("inside");
+    assert!(paths.ensure_inside(&paths.group_file("inbox")).is_ok());
+    // Not created yet
+    assert!(paths.ensure_inside(&paths.root.join("new/dir/file.md")).is_ok());
+
+    let escape = paths.tasks.join("../../elsewhere.md");
+    let err = paths.ensure_inside(&escape).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_out_of_the_root_are_refused() {
+    let paths = temp_vault("symlink");
+    let outside = TempDir::new("outside");
+    let outside = outside.path();
+    fs::write(outside.join("victim.md"), "# Victim\n").unwrap();
+
+    // A group file that is really a link to somewhere else
+    std::os::unix::fs::symlink(outside.join("victim.md"), paths.group_file("victim")).unwrap();
+    let err = store::load_group(&paths, "victim").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert!(store::delete_group(&paths, "victim").is_err());
+    assert!(outside.join("victim.md").exists());
+    // The rest of the vault still loads without it
+    fs::write(paths.group_file("inbox"), "# Inbox\n").unwrap();
+    let groups = store::load_groups(&paths).unwrap();
+    assert_eq!(groups.iter().map(|g| g.id.as_str()).collect::<Vec<_>>(), ["inbox"]);
+
+    // A whole folder of the layout replaced by a link
+    fs::remove_dir_all(&paths.progress).unwrap();
+    std::os::unix::fs::symlink(outside, &paths.progress).unwrap();
+    assert!(store::save_progress(&paths, &[]).is_err());
+    assert!(!outside.join("progress.md").exists());
+}
+
+#[test]
+fn the_outbox_stays_inside_the_vault() {
+    let paths = temp_vault("outbox");
+    let mut settings = Settings::default();
+    assert_eq!(settings.outbox_dir(&paths), paths.root.join("outbox"));
+    settings.outbox = Some("exports/today".into());
+    assert!(paths.ensure_inside(&settings.outbox_dir(&paths)).is_ok());
+
+    // Neither an absolute path nor `..` leads anywhere else
+    for outbox in [std::env::temp_dir(), "../elsewhere".into()] {
+        settings.outbox = Some(outbox);
+        let err = paths.ensure_inside(&settings.outbox_dir(&paths)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,33 @@
+use std::io;
+use std::path::Path;
+
+use nekotick_lib::vault::{ErrorKind, NekoError};
+
+#[test]
+fn serializes_with_stable_codes() {
+    let err = NekoError::not_found("no task \"x\"").with_task("x");
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "kind": "not_found", "message": "no task \"x\"", "taskId": "x" })
+    );
+
+    for kind in [
+        ErrorKind::InvalidInput,
+        ErrorKind::NotFound,
+        ErrorKind::PermissionDenied,
+        ErrorKind::Io,
+        ErrorKind::WindowUnavailable,
+        ErrorKind::Internal,
+    ] {
+        assert_eq!(serde_json::to_value(kind).unwrap(), kind.code());
+    }
+}
+
+#[test]
+fn io_errors_keep_their_kind_and_path() {
+    let err = NekoError::io(io::Error::from(io::ErrorKind::PermissionDenied), Path::new("a.md"));
+    assert_eq!(err.kind, ErrorKind::PermissionDenied);
+    assert_eq!(err.path.as_deref(), Some(Path::new("a.md")));
+    assert!(err.to_string().starts_with("a.md: "));
+}
@@ -0,0 +1,153 @@
+// One hand-written file per format: the fields it parses into, and the exact
+// text it serializes back to.
+use nekotick_lib::vault::progress::{self, Direction, Frequency, ProgressEntry};
+use nekotick_lib::vault::tasks::{self, Interval, LineEnding, NoteLine, Priority};
+use nekotick_lib::vault::time_log::{self, AppUsage};
+
+const GROUP: &str = "# 工作
+
+format: 1
+pinned: true
+created: 1733000000000
+updated: 1733000500000
+
+Notes stay where they were.
+
+- [ ] Ship the release <!--id:rel,created:1733000000000,order:0,priority:red,est:90,log:10-20;30-40,color:blue-->
+  - [x] Write changelog <!--id:log,created:1733000001000,order:0,time:09:30,completedAt:1733000400000,parent:rel-->
+
+And so do notes between tasks.
+";
+
+const PROGRESS: &str = "# 进度列表
+
+## 读完《SICP》
+- id: sicp
+- type: progress
+- note: 每天至少一节
+- direction: increment
+- total: 400
+- step: 5
+- unit: 页
+- current: 125
+- todayCount: 10
+- lastUpdateDate: Fri Oct 16 2026
+- startDate: 1733000000000
+- createdAt: 1733000000000
+
+## Water
+- id: water
+- type: counter
+- step: 1
+- unit: cups
+- current: 3
+- todayCount: 3
+- frequency: daily
+- createdAt: 1733000000000
+";
+
+const TIME_LOG: &str = "# 时间记录
+
+## 2024-11-30
+
+### 应用使用时间
+- Firefox: 3600秒
+
+### 网站访问时间
+- localhost:3000: 60秒
+
+## 2024-12-03
+
+### 番茄钟
+- 完成: 3
+";
+
+#[test]
+fn a_group_file_parses_into_tasks_and_back() {
+    let group = tasks::parse_group("work", GROUP);
+    assert_eq!(
+        (group.id.as_str(), group.name.as_str(), group.pinned),
+        ("work", "工作", true)
+    );
+    assert_eq!((group.created_at, group.updated_at), (1733000000000, 1733000500000));
+    let note = |after: Option<&str>, text: &str| NoteLine {
+        after: after.map(str::to_string),
+        text: text.to_string(),
+    };
+    assert_eq!(
+        group.extra,
+        [
+            note(None, "Notes stay where they were."),
+            note(None, ""),
+            note(Some("log"), ""),
+            note(Some("log"), "And so do notes between tasks."),
+        ]
+    );
+
+    let [release, changelog] = &group.tasks[..] else {
+        panic!("expected two tasks, got {:?}", group.tasks);
+    };
+    assert_eq!(
+        (release.content.as_str(), release.completed),
+        ("Ship the release", false)
+    );
+    assert_eq!(release.priority, Some(Priority::Red));
+    assert_eq!(release.estimated_minutes, Some(90));
+    assert_eq!(
+        release.intervals,
+        [Interval { start: 10, end: 20 }, Interval { start: 30, end: 40 }]
+    );
+    assert_eq!(release.extra, [("color".to_string(), "blue".to_string())]);
+    assert!(changelog.completed);
+    assert_eq!(changelog.completed_at, Some(1733000400000));
+    assert_eq!(changelog.scheduled_time.as_deref(), Some("09:30"));
+    assert_eq!(changelog.parent_id.as_deref(), Some("rel"));
+
+    assert_eq!(tasks::serialize_group(&group), GROUP);
+
+    // Windows line endings stay as they were
+    let crlf = GROUP.replace('\n', "\r\n");
+    let group = tasks::parse_group("work", &crlf);
+    assert_eq!(group.line_ending, LineEnding::Crlf);
+    assert_eq!(tasks::serialize_group(&group), crlf);
+}
+
+#[test]
+fn a_progress_file_parses_into_items_and_back() {
+    let items = progress::parse_progress(PROGRESS);
+    let [ProgressEntry::Progress(book), ProgressEntry::Counter(water)] = &items[..] else {
+        panic!("expected a progress item and a counter, got {:?}", items);
+    };
+    assert_eq!((book.id.as_str(), book.title.as_str()), ("sicp", "读完《SICP》"));
+    assert_eq!(book.note.as_deref(), Some("每天至少一节"));
+    assert_eq!(book.direction, Direction::Increment);
+    assert_eq!(
+        (book.total, book.step, book.current, book.today_count),
+        (400, 5, 125, 10)
+    );
+    assert_eq!(book.last_update_date.as_deref(), Some("Fri Oct 16 2026"));
+    assert_eq!((book.start_date, book.end_date), (Some(1733000000000), None));
+    assert_eq!((water.unit.as_str(), water.current), ("cups", 3));
+    assert_eq!(water.frequency, Frequency::Daily);
+
+    assert_eq!(progress::serialize_progress(&items), PROGRESS);
+}
+
+#[test]
+fn a_time_log_parses_into_days_and_back() {
+    let days = time_log::parse_time_log(TIME_LOG);
+    let usage = |name: &str, duration| AppUsage {
+        name: name.to_string(),
+        duration,
+    };
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date, "2024-11-30");
+    assert_eq!(days[0].apps, [usage("Firefox", 3600)]);
+    // Only the last `: ` separates the name, so ports survive
+    assert_eq!(days[0].websites, [usage("localhost:3000", 60)]);
+    assert_eq!(days[0].pomodoros, 0);
+    assert_eq!((days[1].date.as_str(), days[1].pomodoros), ("2024-12-03", 3));
+    assert!(days[1].apps.is_empty() && days[1].websites.is_empty());
+
+    assert_eq!(time_log::serialize_time_log(&days), TIME_LOG);
+}
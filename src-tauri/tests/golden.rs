@@ -0,0 +1,175 @@
+// Golden files: every fixture in `tests/fixtures` is parsed, re-serialized
+// and compared with its snapshot in `tests/snapshots`. Snapshots are the
+// canonical form, so they must also survive a round trip byte for byte.
+// After a deliberate format change, regenerate them with
+// `UPDATE_SNAPSHOTS=1 cargo test --test golden` and review the diff.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use nekotick_lib::vault::{progress, tasks, time_log, Group, LineEnding};
+
+fn fixtures(kind: &str) -> Vec<PathBuf> {
+    files("tests/fixtures", kind)
+}
+
+fn files(root: &str, kind: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(root).join(kind);
+    let mut files: Vec<PathBuf> = fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+    files.sort();
+    files
+}
+
+fn check_snapshot(kind: &str, fixture: &Path, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(kind)
+        .join(fixture.file_name().unwrap());
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| panic!("missing snapshot {}", path.display()));
+    assert_eq!(actual, expected, "snapshot mismatch for {}", fixture.display());
+}
+
+// Ids and timestamps the parser makes up for missing metadata are random,
+// so pin them before comparing. Tasks get 1, as `created:0` reads as missing.
+fn stabilize(group: &mut Group, source: &str, started: i64) {
+    let mut renamed = HashMap::new();
+    for (i, task) in group.tasks.iter_mut().enumerate() {
+        if !source.contains(&format!("id:{}", task.id)) {
+            let new_id = format!("new-{}", i);
+            renamed.insert(std::mem::replace(&mut task.id, new_id.clone()), new_id);
+        }
+        if task.created_at >= started {
+            task.created_at = 1;
+        }
+    }
+    // Inferred parents point at generated ids too, and so do notes
+    for task in group.tasks.iter_mut() {
+        if let Some(new_id) = task.parent_id.as_ref().and_then(|p| renamed.get(p)) {
+            task.parent_id = Some(new_id.clone());
+        }
+    }
+    for note in group.extra.iter_mut() {
+        if let Some(new_id) = note.after.as_ref().and_then(|a| renamed.get(a)) {
+            note.after = Some(new_id.clone());
+        }
+    }
+    if group.created_at >= started {
+        group.created_at = 0;
+    }
+    if group.updated_at >= started {
+        group.updated_at = 0;
+    }
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[test]
+fn group_files_match_snapshots() {
+    for fixture in fixtures("groups") {
+        let source = fs::read_to_string(&fixture).unwrap();
+        let started = now_millis();
+        let mut group = tasks::parse_group("fixture", &source);
+        stabilize(&mut group, &source, started);
+        let text = tasks::serialize_group(&group);
+        check_snapshot("groups", &fixture, &text);
+
+        let again = tasks::parse_group("fixture", &text);
+        assert_eq!(again, group, "{} changed on a second parse", fixture.display());
+        assert_eq!(
+            tasks::serialize_group(&again),
+            text,
+            "{} is not a fixed point",
+            fixture.display()
+        );
+    }
+}
+
+#[test]
+fn progress_files_match_snapshots() {
+    for fixture in fixtures("progress") {
+        let source = fs::read_to_string(&fixture).unwrap();
+        let entries = progress::parse_progress(&source);
+        let text = progress::serialize_progress(&entries);
+        check_snapshot("progress", &fixture, &text);
+
+        let again = progress::parse_progress(&text);
+        assert_eq!(again, entries);
+        assert_eq!(progress::serialize_progress(&again), text);
+    }
+}
+
+#[test]
+fn time_logs_match_snapshots() {
+    for fixture in fixtures("time-log") {
+        let source = fs::read_to_string(&fixture).unwrap();
+        let days = time_log::parse_time_log(&source);
+        let text = time_log::serialize_time_log(&days);
+        check_snapshot("time-log", &fixture, &text);
+
+        let again = time_log::parse_time_log(&text);
+        assert_eq!(again, days);
+        assert_eq!(time_log::serialize_time_log(&again), text);
+    }
+}
+
+// Straight from the bytes on disk, CRLF and notes between tasks included
+#[test]
+fn canonical_files_come_back_byte_for_byte() {
+    for kind in ["groups", "progress", "time-log"] {
+        for snapshot in files("tests/snapshots", kind) {
+            let bytes = fs::read(&snapshot).unwrap();
+            let source = String::from_utf8(bytes.clone()).unwrap();
+            let text = match kind {
+                "groups" => tasks::serialize_group(&tasks::parse_group("fixture", &source)),
+                "progress" => progress::serialize_progress(&progress::parse_progress(&source)),
+                _ => time_log::serialize_time_log(&time_log::parse_time_log(&source)),
+            };
+            assert_eq!(text.as_bytes(), bytes, "{} changed", snapshot.display());
+        }
+    }
+    assert!(
+        fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/groups/crlf.md"))
+            .unwrap()
+            .ends_with(b"\r\n")
+    );
+}
+
+#[test]
+fn nothing_is_dropped_from_damaged_groups() {
+    let source =
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/groups/duplicate_ids.md"))
+            .unwrap();
+    let group = tasks::parse_group("dup", &source);
+    let text = tasks::serialize_group(&group);
+    for content in [
+        "First copy",
+        "Second copy",
+        "Child of the duplicate",
+        "Orphan whose parent",
+        "Loop A",
+        "Loop B",
+    ] {
+        assert!(text.contains(content), "{} was dropped", content);
+    }
+    assert!(text.contains("parent:gone"));
+}
+
+#[test]
+fn crlf_files_parse_like_lf_files() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/groups");
+    let crlf = fs::read_to_string(dir.join("crlf.md")).unwrap();
+    assert!(crlf.contains("\r\n"));
+    let lf = crlf.replace("\r\n", "\n");
+    // Only the remembered line ending differs
+    let mut group = tasks::parse_group("g", &crlf);
+    assert_eq!(group.line_ending, LineEnding::Crlf);
+    group.line_ending = LineEnding::Lf;
+    assert_eq!(group, tasks::parse_group("g", &lf));
+}
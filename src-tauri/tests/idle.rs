@@ -0,0 +1,145 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use nekotick_lib::idle::{IdleEvent, IdleSource, IdleWatch};
+use nekotick_lib::vault::pomodoro::{Phase, PomodoroConfig};
+use nekotick_lib::vault::timer::{IdleChoice, Timers};
+use nekotick_lib::vault::Interval;
+
+const MIN: i64 = 60_000;
+const THRESHOLD: Option<Duration> = Some(Duration::from_secs(5 * 60));
+
+// Idle time set by the test, as a display server would report it
+struct FakeSource(Rc<Cell<Option<Duration>>>);
+
+impl IdleSource for FakeSource {
+    fn idle_for(&mut self) -> Option<Duration> {
+        self.0.get()
+    }
+}
+
+fn minutes(n: u64) -> Option<Duration> {
+    Some(Duration::from_secs(n * 60))
+}
+
+#[test]
+fn the_watch_reports_leaving_and_coming_back_once() {
+    let idle = Rc::new(Cell::new(minutes(1)));
+    let mut watch = IdleWatch::new(Box::new(FakeSource(idle.clone())));
+    assert_eq!(watch.poll(60 * MIN, THRESHOLD), None);
+
+    idle.set(minutes(5));
+    assert_eq!(
+        watch.poll(60 * MIN, THRESHOLD),
+        Some(IdleEvent::Away { since: 55 * MIN })
+    );
+    idle.set(minutes(30));
+    assert_eq!(watch.poll(80 * MIN, THRESHOLD), None);
+    // An unreadable source changes nothing
+    idle.set(None);
+    assert_eq!(watch.poll(85 * MIN, THRESHOLD), None);
+
+    idle.set(minutes(0));
+    assert_eq!(watch.poll(90 * MIN, THRESHOLD), Some(IdleEvent::Back { at: 90 * MIN }));
+    assert_eq!(watch.poll(91 * MIN, THRESHOLD), None);
+
+    // Turning detection off while away counts as coming back
+    idle.set(minutes(10));
+    assert!(watch.poll(100 * MIN, THRESHOLD).is_some());
+    assert_eq!(watch.poll(101 * MIN, None), Some(IdleEvent::Back { at: 101 * MIN }));
+}
+
+#[test]
+fn idle_time_is_kept_discarded_or_reassigned() {
+    let away = Interval {
+        start: 10 * MIN,
+        end: 70 * MIN,
+    };
+    let cases = [
+        (IdleChoice::Keep, vec![("a".to_string(), away)]),
+        (IdleChoice::Discard, vec![]),
+        (
+            IdleChoice::Reassign {
+                task_id: "lunch".into(),
+            },
+            vec![("lunch".to_string(), away)],
+        ),
+    ];
+    for (choice, logged) in cases {
+        let mut timers = Timers::default();
+        timers.start("a", 0, false);
+
+        let paused = timers.go_idle(10 * MIN);
+        assert_eq!(
+            paused.sessions,
+            [(
+                "a".to_string(),
+                Interval {
+                    start: 0,
+                    end: 10 * MIN
+                }
+            )]
+        );
+        assert!(timers.running.is_empty());
+        assert!(timers.go_idle(20 * MIN).sessions.is_empty());
+
+        assert!(timers.come_back(70 * MIN));
+        assert!(!timers.come_back(75 * MIN));
+        // Answered later, the timer still resumes from the return
+        let resolved = timers.resolve_idle(choice, 72 * MIN);
+        assert_eq!(resolved.sessions, logged);
+        assert_eq!(timers.running[0].task_id, "a");
+        assert_eq!(timers.running[0].started_at, 70 * MIN);
+        assert_eq!(timers.idle, None);
+    }
+}
+
+#[test]
+fn idleness_without_a_running_timer_is_ignored() {
+    let mut timers = Timers::default();
+    assert_eq!(timers.go_idle(0), Default::default());
+    assert!(!timers.come_back(10));
+    assert_eq!(timers.resolve_idle(IdleChoice::Keep, 20), Default::default());
+    assert!(timers.running.is_empty());
+}
+
+#[test]
+fn a_pomodoro_waits_out_the_idle_break() {
+    for (choice, ends_at) in [(IdleChoice::Discard, 85 * MIN), (IdleChoice::Keep, 25 * MIN)] {
+        let mut timers = Timers::default();
+        timers.start_pomodoro(Some("a"), 0, PomodoroConfig::default());
+
+        timers.go_idle(10 * MIN);
+        assert!(timers.running.is_empty());
+        assert!(timers.idle.as_ref().unwrap().pomodoro.is_some());
+        // Nothing finishes while the user is away
+        assert_eq!(timers.advance(60 * MIN), Default::default());
+        timers.come_back(70 * MIN);
+
+        timers.resolve_idle(choice, 72 * MIN);
+        let session = timers.pomodoro.session.as_ref().unwrap();
+        assert_eq!((session.phase, session.task_id.as_deref()), (Phase::Work, Some("a")));
+        // Time away discarded doesn't count toward the pomodoro either
+        assert_eq!(session.ends_at, ends_at);
+        assert!(timers.is_running("a"));
+    }
+
+    let mut timers = Timers::default();
+    timers.start_pomodoro(Some("a"), 0, PomodoroConfig::default());
+    timers.go_idle(10 * MIN);
+    timers.come_back(70 * MIN);
+    timers.resolve_idle(IdleChoice::Discard, 70 * MIN);
+    let finished = timers.advance(85 * MIN);
+    assert_eq!(finished.pomodoros, [85 * MIN]);
+    assert_eq!(
+        finished.sessions,
+        [(
+            "a".to_string(),
+            Interval {
+                start: 70 * MIN,
+                end: 85 * MIN
+            }
+        )]
+    );
+}
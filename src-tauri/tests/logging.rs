@@ -0,0 +1,93 @@
+mod common;
+
+use std::fs;
+use std::io::Read;
+
+use common::{temp_vault, TempDir};
+use nekotick_lib::diagnostics;
+use nekotick_lib::logging::{rotated_path, RotatingFile};
+
+#[test]
+fn rotates_and_keeps_a_bounded_number_of_files() {
+    let temp = TempDir::new("rotate");
+    let dir = temp.path();
+    let mut file = RotatingFile::new(dir, 100, 2);
+    for i in 0..20 {
+        file.write_line(&format!("line {:02} {}", i, "x".repeat(30))).unwrap();
+    }
+
+    let current = fs::read_to_string(file.path()).unwrap();
+    assert!(current.ends_with(&format!("line 19 {}\n", "x".repeat(30))));
+    assert!(fs::metadata(file.path()).unwrap().len() <= 100);
+    assert!(rotated_path(dir, 1).exists());
+    assert!(rotated_path(dir, 2).exists());
+    assert!(!rotated_path(dir, 3).exists());
+    // Older generations hold older lines
+    let older = fs::read_to_string(rotated_path(dir, 2)).unwrap();
+    assert!(older < fs::read_to_string(rotated_path(dir, 1)).unwrap());
+}
+
+#[test]
+fn report_has_logs_and_a_redacted_layout() {
+    let paths = temp_vault("diagnostics");
+    fs::write(paths.group_file("secret plan"), "- [ ] buy a ring").unwrap();
+    fs::create_dir_all(paths.logs_dir()).unwrap();
+    fs::write(paths.logs_dir().join("nekotick.log"), "hello\n").unwrap();
+
+    let out = paths.root.join("report.zip");
+    diagnostics::write_report(&out, &paths, "nekotick test\n").unwrap();
+
+    let mut zip = zip::ZipArchive::new(fs::File::open(&out).unwrap()).unwrap();
+    let mut read = |name: &str| {
+        let mut text = String::new();
+        zip.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+        text
+    };
+    assert_eq!(read("versions.txt"), "nekotick test\n");
+    assert_eq!(read("logs/nekotick.log"), "hello\n");
+    let layout = read("vault-layout.txt");
+    assert!(layout.contains("tasks/"));
+    assert!(layout.contains("logs/nekotick.log 6"));
+    assert!(!layout.contains("secret"));
+    assert!(layout.lines().any(|l| l.starts_with("tasks/") && l.ends_with(".md 16")));
+}
+
+#[test]
+fn log_lines_lose_group_names_and_data_paths() {
+    let paths = temp_vault("redact");
+    fs::write(paths.group_file("plan"), "- [ ] a").unwrap();
+    fs::write(paths.group_file("plan-b"), "- [ ] b").unwrap();
+    let log = format!(
+        "watching {}\nfailed to reload group plan-b: bad\nskipping group plan: bad\nplanned default\n",
+        paths.root.display()
+    );
+
+    let redacted = diagnostics::redact_log(&log, &paths);
+    assert!(!redacted.contains("plan-b") && !redacted.contains(" plan:"));
+    assert!(!redacted.contains(&paths.root.display().to_string()));
+    assert!(redacted.starts_with("watching <data>\n"));
+    // Words that merely contain an id, and the fixed names, stay readable
+    assert!(redacted.ends_with("planned default\n"));
+}
+
+#[test]
+fn frontend_lines_keep_only_their_time_and_level() {
+    let paths = temp_vault("redact-frontend");
+    let log = "2026-10-16T09:30:00.000+02:00 ERROR frontend: Failed to save \"buy a ring for Sam\"\n\
+               at saveGroup (storage.ts:12)\n\
+               2026-10-16T09:30:01.000+02:00 WARN  nekotick_lib::storage: save failed: disk full\n\
+               not a log line\n";
+
+    let redacted = diagnostics::redact_log(log, &paths);
+    assert!(
+        !redacted.contains("ring") && !redacted.contains("saveGroup"),
+        "{}",
+        redacted
+    );
+    assert_eq!(
+        redacted,
+        "2026-10-16T09:30:00.000+02:00 ERROR frontend: <redacted>\n\
+         2026-10-16T09:30:01.000+02:00 WARN  nekotick_lib::storage: save failed: disk full\n\
+         not a log line\n"
+    );
+}
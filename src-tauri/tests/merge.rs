@@ -0,0 +1,136 @@
+use nekotick_lib::vault::audit;
+use nekotick_lib::vault::merge::{merge_groups, CONFLICT_KEY, CONFLICT_PREFIX};
+use nekotick_lib::vault::tasks::{self, Priority, Task};
+use nekotick_lib::vault::Group;
+
+const BASE: &str = "# 收集箱
+
+- [ ] a <!-- id:a,created:1,order:0 -->
+- [ ] b <!-- id:b,created:1,order:1 -->
+- [ ] c <!-- id:c,created:1,order:2 -->";
+
+fn group(text: &str) -> Group {
+    tasks::parse_group("inbox", text)
+}
+
+fn task<'a>(group: &'a Group, id: &str) -> Option<&'a Task> {
+    group.tasks.iter().find(|t| t.id == id)
+}
+
+#[test]
+fn edits_to_different_tasks_and_fields_both_land() {
+    let base = group(BASE);
+    let ours = group(
+        &BASE
+            .replace(
+                "- [ ] a <!-- id:a,created:1,order:0 -->",
+                "- [x] a <!-- id:a,created:1,order:0,completedAt:5 -->",
+            )
+            .replace("order:2 -->", "order:2 -->\n- [ ] ours <!-- id:o,created:2,order:3 -->"),
+    );
+    let theirs = group(
+        &BASE
+            .replace("- [ ] a <!--", "- [ ] a, reworded <!--")
+            .replace(
+                "- [ ] b <!-- id:b,created:1,order:1",
+                "- [ ] b <!-- id:b,created:1,order:1,priority:red",
+            )
+            .replace(
+                "order:2 -->",
+                "order:2 -->\n- [ ] theirs <!-- id:t,created:3,order:4 -->",
+            ),
+    );
+
+    let outcome = merge_groups(&base, &ours, &theirs);
+    assert!(outcome.conflicts.is_empty());
+    let merged = &outcome.group;
+    let a = task(merged, "a").unwrap();
+    assert_eq!(
+        (a.content.as_str(), a.completed, a.completed_at),
+        ("a, reworded", true, Some(5))
+    );
+    assert_eq!(task(merged, "b").unwrap().priority, Some(Priority::Red));
+    let ids: Vec<&str> = merged.tasks.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["a", "b", "c", "o", "t"]);
+}
+
+#[test]
+fn the_same_field_changed_twice_keeps_ours_and_marks_theirs() {
+    let base = group(BASE);
+    let ours = group(&BASE.replace("- [ ] b <!--", "- [ ] b, ours <!--"));
+    let theirs = group(
+        &BASE
+            .replace("- [ ] b <!--", "- [ ] b, theirs <!--")
+            .replace("# 收集箱", "# Inbox"),
+    );
+
+    let outcome = merge_groups(&base, &ours, &theirs);
+    assert_eq!(outcome.conflicts.len(), 1);
+    let conflict = &outcome.conflicts[0];
+    assert_eq!((conflict.task_id.as_deref(), conflict.field), (Some("b"), "content"));
+    // Only they renamed the group, so that isn't a conflict
+    assert_eq!(outcome.group.name, "Inbox");
+
+    let contents: Vec<&str> = outcome.group.tasks.iter().map(|t| t.content.as_str()).collect();
+    assert_eq!(
+        contents,
+        ["a", "b, ours", &format!("{}b, theirs", CONFLICT_PREFIX), "c"]
+    );
+    let marker = &outcome.group.tasks[2];
+    assert_ne!(marker.id, "b");
+    assert!(marker.extra.contains(&(CONFLICT_KEY.to_string(), "b".to_string())));
+    // The marker survives a save and reload
+    let reloaded = group(&tasks::serialize_group(&outcome.group));
+    assert_eq!(
+        tasks::serialize_group(&reloaded),
+        tasks::serialize_group(&outcome.group)
+    );
+}
+
+#[test]
+fn a_deleted_task_comes_back_only_if_the_other_side_edited_it() {
+    let base = group(BASE);
+    // We deleted `a` and `b`; they deleted `c` and edited `b`
+    let ours = group(
+        &BASE
+            .replace("- [ ] a <!-- id:a,created:1,order:0 -->\n", "")
+            .replace("- [ ] b <!-- id:b,created:1,order:1 -->\n", ""),
+    );
+    let theirs = group(
+        &BASE
+            .replace("- [ ] b <!--", "- [ ] b, edited <!--")
+            .replace("\n- [ ] c <!-- id:c,created:1,order:2 -->", ""),
+    );
+
+    let outcome = merge_groups(&base, &ours, &theirs);
+    assert!(outcome.conflicts.is_empty());
+    let contents: Vec<&str> = outcome.group.tasks.iter().map(|t| t.content.as_str()).collect();
+    assert_eq!(contents, ["b, edited"]);
+
+    // Deleted by them but edited by us, the task stays
+    let ours = group(&BASE.replace("- [ ] c <!--", "- [ ] c, edited <!--"));
+    let outcome = merge_groups(&base, &ours, &theirs);
+    assert!(task(&outcome.group, "c").is_some_and(|c| c.content == "c, edited"));
+}
+
+#[test]
+fn a_conflict_marker_does_not_count_the_time_twice() {
+    let timed = BASE.replace("order:1 -->", "order:1,est:30,act:5,log:0-600000 -->");
+    let base = group(&timed);
+    let ours = group(&timed.replace("- [ ] b <!--", "- [ ] b, ours <!--"));
+    let theirs = group(&timed.replace("- [ ] b <!--", "- [ ] b, theirs <!--"));
+
+    let outcome = merge_groups(&base, &ours, &theirs);
+    assert_eq!(outcome.conflicts.len(), 1);
+    assert_eq!(outcome.group.tasks.len(), 4);
+    let summary = audit::summarize(std::slice::from_ref(&outcome.group));
+    assert_eq!(summary, audit::summarize(&[ours]));
+    assert_eq!(
+        (
+            summary.total.tasks,
+            summary.total.estimated_minutes,
+            summary.total.actual_minutes
+        ),
+        (1, 30, 15)
+    );
+}
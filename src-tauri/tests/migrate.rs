@@ -0,0 +1,51 @@
+use chrono::{Local, TimeZone};
+use nekotick_lib::vault::{check, migrate, tasks};
+
+const LEGACY: &str = "# Nekotick Tasks
+
+- [ ] Write report {est:30} {act:45} <!-- id:old-1 created:1700000000000 -->
+- [x] Ship it {done:2024-01-15} <!-- id:old-2 created:1700000000001 -->
+- [ ] Already moved <!-- id:kept created:1700000000002 -->
+";
+
+#[test]
+fn legacy_tags_become_metadata() {
+    let group = tasks::parse_group("legacy", LEGACY);
+    assert_eq!(group.tasks[0].content, "Write report");
+    assert_eq!(group.tasks[0].created_at, 1700000000000);
+    assert_eq!(group.tasks[0].estimated_minutes, Some(30));
+    assert_eq!(group.tasks[0].actual_minutes, Some(45));
+
+    let done = Local
+        .timestamp_millis_opt(group.tasks[1].completed_at.unwrap())
+        .unwrap();
+    assert_eq!(done.format("%Y-%m-%d %H:%M").to_string(), "2024-01-15 00:00");
+
+    // Once written, the file says which format it is and tags are plain text
+    let saved = tasks::serialize_group(&group);
+    assert!(saved.contains(&format!("\nformat: {}\n", tasks::FORMAT_VERSION)));
+    let versioned = saved.replace("Write report", "Write {est:5} report");
+    let reparsed = tasks::parse_group("legacy", &versioned);
+    assert_eq!(reparsed.tasks[0].content, "Write {est:5} report");
+    assert_eq!(reparsed.tasks[0].estimated_minutes, Some(30));
+}
+
+#[test]
+fn migration_skips_tasks_already_in_the_vault() {
+    let mut vault = vec![tasks::parse_group(
+        "nekotick-md",
+        "# Taken\n\n- [ ] Already moved <!--id:kept,created:1,order:0-->",
+    )];
+
+    let first = migrate::migrate(&vault, LEGACY);
+    assert_eq!((first.imported, first.skipped), (2, 1));
+    let group = first.group.unwrap();
+    assert_eq!(group.id, "nekotick-md-2");
+    assert_eq!(group.name, "Nekotick Tasks");
+    vault.push(group);
+    assert!(check::check(&vault).is_empty());
+
+    let again = migrate::migrate(&vault, LEGACY);
+    assert_eq!((again.imported, again.skipped), (0, 3));
+    assert!(again.group.is_none());
+}
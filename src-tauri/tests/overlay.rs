@@ -0,0 +1,87 @@
+use nekotick_lib::overlay::{overlay_width, DragItem, OverlayData, MAX_ROWS};
+use nekotick_lib::vault::Priority;
+
+const HOSTILE: &[&str] = &[
+    "<img src=x onerror=alert(1)>",
+    "${alert(document.cookie)}",
+    "`); alert(1); (`",
+    "</script><script>alert(1)</script>",
+    "\"); alert(1); (\"",
+    "line\u{2028}separator\u{2029}paragraph",
+    "a & b <!-- c -->",
+    "\\`${x}\\",
+];
+
+fn item(content: &str, depth: usize) -> DragItem {
+    DragItem {
+        content: content.to_string(),
+        done: false,
+        priority: Some(Priority::Red),
+        depth,
+    }
+}
+
+fn data(content: &str) -> OverlayData {
+    OverlayData::new(vec![item("parent", 0), item(content, 1)], true)
+}
+
+// The overlay page gets the data as the payload of an event
+#[test]
+fn hostile_text_arrives_as_plain_text() {
+    for text in HOSTILE {
+        let payload = serde_json::to_value(data(text)).unwrap();
+        assert_eq!(payload["items"][1]["content"], *text);
+    }
+}
+
+#[test]
+fn data_round_trips_through_the_payload() {
+    let payload = serde_json::to_value(data(HOSTILE[0])).unwrap();
+    assert_eq!(payload["items"][1]["priority"], "red");
+    assert_eq!(payload["items"][1]["depth"], 1);
+    assert_eq!(payload["dark"], true);
+    assert_eq!(payload["count"], 1);
+}
+
+#[test]
+fn unknown_priority_is_rejected() {
+    let item = serde_json::from_str::<DragItem>(r#"{"content":"x","priority":"red;}*{color:red"}"#);
+    assert!(item.is_err());
+}
+
+#[test]
+fn long_subtrees_are_capped() {
+    let items: Vec<DragItem> = (0..MAX_ROWS + 5).map(|i| item(&i.to_string(), 1)).collect();
+    let data = OverlayData::new(items, false);
+    assert_eq!(data.items.len(), MAX_ROWS);
+    assert_eq!(data.more, 5);
+
+    let exact = OverlayData::new((0..MAX_ROWS).map(|i| item(&i.to_string(), 0)).collect(), false);
+    assert_eq!(exact.more, 0);
+}
+
+#[test]
+fn height_follows_rows_lines_and_footer() {
+    let one = OverlayData::new(vec![item("a", 0)], false);
+    // Same as the old single-task card
+    assert_eq!(one.height(), 38.0);
+
+    let two = OverlayData::new(vec![item("a", 0), item("b", 1)], false);
+    let multiline = OverlayData::new(vec![item("a\nb", 0)], false);
+    assert!(two.height() > one.height());
+    assert!(multiline.height() > one.height());
+    assert!(multiline.height() < two.height());
+
+    let capped = OverlayData::new((0..MAX_ROWS + 1).map(|i| item(&i.to_string(), 0)).collect(), false);
+    let full = OverlayData::new((0..MAX_ROWS).map(|i| item(&i.to_string(), 0)).collect(), false);
+    assert!(capped.height() > full.height());
+}
+
+#[test]
+fn width_is_clamped() {
+    assert_eq!(overlay_width(None), 350.0);
+    assert_eq!(overlay_width(Some(f64::NAN)), 350.0);
+    assert_eq!(overlay_width(Some(10.0)), 160.0);
+    assert_eq!(overlay_width(Some(5000.0)), 640.0);
+    assert_eq!(overlay_width(Some(420.0)), 420.0);
+}
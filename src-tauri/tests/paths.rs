@@ -0,0 +1,59 @@
+mod common;
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::TempDir;
+use nekotick_lib::vault::paths::{data_dir_arg, resolve_from, DIR_NAME};
+use nekotick_lib::vault::DataDirSource;
+
+fn args(list: &[&str]) -> Vec<OsString> {
+    list.iter().map(OsString::from).collect()
+}
+
+#[test]
+fn the_data_dir_argument_takes_either_form() {
+    assert_eq!(
+        data_dir_arg(args(&["--data-dir", "/srv/neko"])),
+        Some(PathBuf::from("/srv/neko"))
+    );
+    assert_eq!(
+        data_dir_arg(args(&["-v", "--data-dir=/srv/neko"])),
+        Some(PathBuf::from("/srv/neko"))
+    );
+    assert_eq!(data_dir_arg(args(&["--data-dir"])), None);
+    assert_eq!(data_dir_arg(args(&["--data-directory=/srv/neko", "x"])), None);
+}
+
+#[test]
+fn a_portable_folder_wins_then_the_explicit_dir_then_xdg() {
+    let exe = TempDir::new("exe");
+    let explicit = Some(PathBuf::from("/srv/neko"));
+    let xdg = Some(PathBuf::from("/home/me/.local/share"));
+
+    // Without a `NekoTick` folder next to the executable; XDG always exists,
+    // so the explicit dir has to come first to ever be used
+    let paths = resolve_from(Some(exe.path()), explicit.clone(), xdg.clone()).unwrap();
+    assert_eq!(
+        (paths.root.as_path(), paths.source),
+        (Path::new("/srv/neko"), DataDirSource::Explicit)
+    );
+    assert_eq!(paths.tasks, Path::new("/srv/neko/tasks"));
+    assert_eq!(paths.time_tracker, Path::new("/srv/neko/time-tracker"));
+
+    // An empty `NEKOTICK_HOME` counts as unset
+    let paths = resolve_from(Some(exe.path()), Some(PathBuf::new()), xdg.clone()).unwrap();
+    assert_eq!(
+        (paths.root.as_path(), paths.source),
+        (Path::new("/home/me/.local/share/NekoTick"), DataDirSource::Xdg)
+    );
+    assert!(resolve_from(None, None, None).is_none());
+
+    fs::create_dir(exe.path().join(DIR_NAME)).unwrap();
+    let paths = resolve_from(Some(exe.path()), explicit, xdg).unwrap();
+    assert_eq!(
+        (paths.root, paths.source),
+        (exe.path().join(DIR_NAME), DataDirSource::Portable)
+    );
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::fs;
+
+use common::temp_vault;
+use nekotick_lib::vault::pomodoro::{Phase, PomodoroConfig};
+use nekotick_lib::vault::timer::Timers;
+use nekotick_lib::vault::{store, time_log, Interval};
+
+const MIN: i64 = 60_000;
+
+const CONFIG: PomodoroConfig = PomodoroConfig {
+    work_minutes: 25,
+    short_break_minutes: 5,
+    long_break_minutes: 15,
+    long_break_every: 2,
+};
+
+fn phase(timers: &Timers) -> Option<Phase> {
+    timers.pomodoro.session.as_ref().map(|s| s.phase)
+}
+
+#[test]
+fn work_sessions_alternate_with_breaks() {
+    let mut timers = Timers::default();
+    timers.start("other", 0, false);
+    let started = timers.start_pomodoro(Some("a"), 0, CONFIG);
+    assert_eq!(started.sessions, [("other".to_string(), Interval { start: 0, end: 0 })]);
+    assert!(timers.is_running("a"));
+
+    assert!(timers.advance(25 * MIN - 1).sessions.is_empty());
+    let first = timers.advance(25 * MIN);
+    assert_eq!(first.pomodoros, [25 * MIN]);
+    assert_eq!(
+        first.sessions,
+        [(
+            "a".to_string(),
+            Interval {
+                start: 0,
+                end: 25 * MIN
+            }
+        )]
+    );
+    assert_eq!(phase(&timers), Some(Phase::ShortBreak));
+    assert!(!timers.is_running("a"));
+    timers.advance(30 * MIN);
+    assert_eq!(phase(&timers), None);
+
+    // Closed through the second pomodoro and its break: the task only gets
+    // the pomodoro's own 25 minutes, and the break after it is a long one
+    timers.start_pomodoro(Some("a"), 40 * MIN, CONFIG);
+    let second = timers.advance(100 * MIN);
+    assert_eq!(second.pomodoros, [65 * MIN]);
+    assert_eq!(
+        second.sessions,
+        [(
+            "a".to_string(),
+            Interval {
+                start: 40 * MIN,
+                end: 65 * MIN
+            }
+        )]
+    );
+    assert_eq!(phase(&timers), None);
+    assert_eq!(timers.pomodoro.streak, 0);
+}
+
+#[test]
+fn stopping_the_timer_abandons_the_pomodoro() {
+    let mut timers = Timers::default();
+    timers.start_pomodoro(Some("a"), 0, CONFIG);
+    // Switching tasks stops `a`'s timer and with it the pomodoro
+    timers.start("b", 10 * MIN, false);
+    assert_eq!(phase(&timers), None);
+    assert!(timers.advance(60 * MIN).pomodoros.is_empty());
+
+    timers.start_pomodoro(Some("b"), 60 * MIN, CONFIG);
+    let stopped = timers.stop_pomodoro(70 * MIN);
+    // The time worked stays on the task, uncounted as a pomodoro
+    assert_eq!(
+        stopped.sessions,
+        [(
+            "b".to_string(),
+            Interval {
+                start: 10 * MIN,
+                end: 70 * MIN
+            }
+        )]
+    );
+    assert!(stopped.pomodoros.is_empty());
+    assert!(timers.running.is_empty());
+}
+
+#[test]
+fn daily_counts_go_to_the_time_log() {
+    let paths = temp_vault("pomodoro");
+    fs::write(
+        paths.time_log_file(),
+        "# 时间记录\n\n## 2000-01-01\n\n### 应用使用时间\n- Firefox: 60秒\n",
+    )
+    .unwrap();
+
+    let noon = chrono::NaiveDate::from_ymd_opt(2000, 1, 2)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    let noon = noon.and_local_timezone(chrono::Local).unwrap().timestamp_millis();
+    assert_eq!(time_log::local_day(noon), "2000-01-02");
+    store::record_pomodoros(&paths, &[noon, noon + 30 * MIN]).unwrap();
+    store::record_pomodoros(&paths, &[noon + 60 * MIN]).unwrap();
+
+    let days = store::load_time_log(&paths).unwrap();
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].apps[0].name, "Firefox");
+    assert_eq!((days[1].date.as_str(), days[1].pomodoros), ("2000-01-02", 3));
+}
@@ -0,0 +1,314 @@
+// Property tests: any group, progress list or time log the app can hold
+// survives serialize -> parse unchanged, and serializing is a fixed point.
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+use nekotick_lib::vault::progress::{self, CounterItem, Direction, Frequency, ProgressEntry, ProgressItem};
+use nekotick_lib::vault::time_log::{self, AppUsage, DayTimeData};
+use nekotick_lib::vault::{tasks, Group, Interval, LineEnding, NoteLine, Priority, Task};
+
+// Text as users type it: CJK, punctuation and things that look like markup,
+// trimmed and on one line
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 收集箱任务完成中文日本語한국어,.:;!?#*()\\[\\]<>/-]{1,30}"
+        .prop_map(|s| s.trim().to_string())
+        .prop_filter("blank", |s| !s.is_empty())
+}
+
+fn token() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9_-]{1,12}"
+}
+
+fn priority() -> impl Strategy<Value = Option<Priority>> {
+    // `default` is written as no priority at all
+    option::of(prop_oneof![
+        Just(Priority::Red),
+        Just(Priority::Yellow),
+        Just(Priority::Purple),
+        Just(Priority::Green),
+    ])
+}
+
+fn extra_meta() -> impl Strategy<Value = Vec<(String, String)>> {
+    vec(("x[a-z]{1,8}", "[a-zA-Z0-9]{0,8}"), 0..3)
+}
+
+// Estimate, actual and the session log
+fn audit() -> impl Strategy<Value = (Option<u32>, Option<u32>, Vec<Interval>)> {
+    (
+        option::of(any::<u32>()),
+        option::of(any::<u32>()),
+        vec(
+            (0i64..1 << 50, 0i64..1 << 50).prop_map(|(start, end)| Interval { start, end }),
+            0..3,
+        ),
+    )
+}
+
+// Note lines with the index of the task they follow, if any; a blank line
+// only counts as a note once a task came before it
+fn notes() -> impl Strategy<Value = Vec<(Option<usize>, String)>> {
+    let note = prop_oneof![
+        (option::of(0usize..8), "> [a-zA-Z0-9 ]{0,12}"),
+        (0usize..8).prop_map(|i| (Some(i), String::new())),
+    ];
+    vec(note, 0..4)
+}
+
+fn group() -> impl Strategy<Value = Group> {
+    let task = (
+        text(),
+        any::<bool>(),
+        1i64..=i64::MAX,
+        option::of(any::<i64>()),
+        option::of("[0-9]{2}:[0-9]{2}"),
+        any::<i64>(),
+        any::<bool>(),
+        priority(),
+        extra_meta(),
+        audit(),
+        // Index of the parent among the tasks, or a dangling id
+        option::of(prop_oneof![(0usize..8).prop_map(Ok), token().prop_map(Err)]),
+    );
+    (
+        text(),
+        any::<bool>(),
+        any::<i64>(),
+        any::<i64>(),
+        vec(task, 0..8),
+        notes(),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(name, pinned, created_at, updated_at, raw_tasks, notes, crlf, final_newline)| {
+                let count = raw_tasks.len();
+                let extra = notes
+                    .into_iter()
+                    .map(|(after, text)| NoteLine {
+                        after: after.filter(|_| count > 0).map(|i| format!("t{}", i % count)),
+                        text,
+                    })
+                    // A blank last line reads back as the final newline
+                    .filter(|note| !note.text.is_empty() || (note.after.is_some() && final_newline))
+                    .collect();
+                let tasks = raw_tasks
+                    .into_iter()
+                    .enumerate()
+                    .map(
+                        |(
+                            i,
+                            (
+                                content,
+                                completed,
+                                created,
+                                completed_at,
+                                time,
+                                order,
+                                collapsed,
+                                priority,
+                                extra,
+                                audit,
+                                parent,
+                            ),
+                        )| {
+                            Task {
+                                id: format!("t{}", i),
+                                content,
+                                completed,
+                                created_at: created,
+                                completed_at,
+                                scheduled_time: time,
+                                order,
+                                parent_id: parent.map(|p| match p {
+                                    Ok(index) => format!("t{}", index % count),
+                                    Err(id) => format!("gone-{}", id),
+                                }),
+                                collapsed,
+                                priority,
+                                estimated_minutes: audit.0,
+                                actual_minutes: audit.1,
+                                intervals: audit.2,
+                                extra,
+                            }
+                        },
+                    )
+                    .collect();
+                Group {
+                    id: "g".to_string(),
+                    name,
+                    pinned,
+                    tasks,
+                    created_at,
+                    updated_at,
+                    extra,
+                    line_ending: if crlf { LineEnding::Crlf } else { LineEnding::Lf },
+                    final_newline,
+                }
+            },
+        )
+}
+
+// id, title, step, unit, current, todayCount, lastUpdateDate, createdAt, extra
+type Common = (
+    String,
+    String,
+    i64,
+    String,
+    i64,
+    i64,
+    Option<String>,
+    i64,
+    Vec<(String, String)>,
+);
+
+fn common_fields() -> impl Strategy<Value = Common> {
+    (
+        token(),
+        text(),
+        any::<i64>().prop_filter("zero step", |s| *s != 0),
+        "[a-zA-Z页次杯]{0,4}",
+        any::<i64>(),
+        any::<i64>(),
+        option::of(text()),
+        any::<i64>(),
+        vec(("x[a-zA-Z]{1,8}", "[a-zA-Z0-9:]{0,8}"), 0..3),
+    )
+}
+
+fn progress_entry() -> impl Strategy<Value = ProgressEntry> {
+    let progress = (
+        common_fields(),
+        option::of(text()),
+        any::<bool>(),
+        any::<i64>().prop_filter("zero total", |t| *t != 0),
+        option::of(any::<i64>()),
+        option::of(any::<i64>()),
+    )
+        .prop_map(
+            |(
+                (id, title, step, unit, current, today_count, last_update_date, created_at, extra),
+                note,
+                down,
+                total,
+                start_date,
+                end_date,
+            )| {
+                ProgressEntry::Progress(ProgressItem {
+                    id,
+                    title,
+                    note,
+                    direction: if down {
+                        Direction::Decrement
+                    } else {
+                        Direction::Increment
+                    },
+                    total,
+                    step,
+                    unit,
+                    current,
+                    today_count,
+                    last_update_date,
+                    start_date,
+                    end_date,
+                    created_at,
+                    extra,
+                })
+            },
+        );
+    let counter = (
+        common_fields(),
+        prop_oneof![
+            Just(Frequency::Daily),
+            Just(Frequency::Weekly),
+            Just(Frequency::Monthly)
+        ],
+    )
+        .prop_map(
+            |((id, title, step, unit, current, today_count, last_update_date, created_at, extra), frequency)| {
+                ProgressEntry::Counter(CounterItem {
+                    id,
+                    title,
+                    step,
+                    unit,
+                    current,
+                    today_count,
+                    last_update_date,
+                    frequency,
+                    created_at,
+                    extra,
+                })
+            },
+        );
+    prop_oneof![progress, counter]
+}
+
+fn day() -> impl Strategy<Value = DayTimeData> {
+    let usage = ("[a-zA-Z0-9 .:_-]{0,16}[a-zA-Z0-9]", any::<u64>()).prop_map(|(name, duration)| AppUsage {
+        name: name.trim().to_string(),
+        duration,
+    });
+    (
+        2000u32..2100,
+        1u32..=12,
+        1u32..=28,
+        vec(usage.clone(), 0..4),
+        vec(usage, 0..4),
+        0u32..20,
+    )
+        .prop_filter("empty days are dropped", |(_, _, _, apps, sites, pomodoros)| {
+            !apps.is_empty() || !sites.is_empty() || *pomodoros > 0
+        })
+        .prop_map(|(y, m, d, apps, websites, pomodoros)| DayTimeData {
+            date: format!("{:04}-{:02}-{:02}", y, m, d),
+            apps,
+            websites,
+            pomodoros,
+        })
+}
+
+fn sorted(mut tasks: Vec<Task>) -> Vec<Task> {
+    tasks.sort_by(|a, b| a.id.cmp(&b.id));
+    tasks
+}
+
+// Notes come back in file order, not in the order they were generated
+fn sorted_notes(mut notes: Vec<NoteLine>) -> Vec<NoteLine> {
+    notes.sort_by(|a, b| (&a.after, &a.text).cmp(&(&b.after, &b.text)));
+    notes
+}
+
+proptest! {
+    #[test]
+    fn groups_round_trip(group in group()) {
+        let text = tasks::serialize_group(&group);
+        let parsed = tasks::parse_group(&group.id, &text);
+        // Tasks come back in file order, which nests children under parents
+        prop_assert_eq!(sorted(parsed.tasks.clone()), sorted(group.tasks.clone()));
+        prop_assert_eq!(&parsed.name, &group.name);
+        prop_assert_eq!(parsed.pinned, group.pinned);
+        prop_assert_eq!(parsed.created_at, group.created_at);
+        prop_assert_eq!(parsed.updated_at, group.updated_at);
+        prop_assert_eq!(sorted_notes(parsed.extra.clone()), sorted_notes(group.extra.clone()));
+        prop_assert_eq!(parsed.line_ending, group.line_ending);
+        prop_assert_eq!(parsed.final_newline, group.final_newline);
+        prop_assert_eq!(tasks::serialize_group(&parsed), text);
+    }
+
+    #[test]
+    fn progress_round_trips(entries in vec(progress_entry(), 0..6)) {
+        let text = progress::serialize_progress(&entries);
+        let parsed = progress::parse_progress(&text);
+        prop_assert_eq!(&parsed, &entries);
+        prop_assert_eq!(progress::serialize_progress(&parsed), text);
+    }
+
+    #[test]
+    fn time_logs_round_trip(days in vec(day(), 0..5)) {
+        let text = time_log::serialize_time_log(&days);
+        let parsed = time_log::parse_time_log(&text);
+        prop_assert_eq!(&parsed, &days);
+        prop_assert_eq!(time_log::serialize_time_log(&parsed), text);
+    }
+}
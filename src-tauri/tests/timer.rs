@@ -0,0 +1,92 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+
+use common::temp_vault;
+use nekotick_lib::vault::merge::merge_groups;
+use nekotick_lib::vault::timer::{self, Timers};
+use nekotick_lib::vault::{store, tasks, ErrorKind, Interval};
+
+const INBOX: &str = "# 收集箱
+
+- [ ] a <!--id:a,created:1,order:0-->
+- [ ] b <!--id:b,created:1,order:1,log:0-1000-->
+- [ ] ab <!--id:ab,created:1,order:2-->";
+
+#[test]
+fn starting_one_timer_stops_the_others_unless_concurrent() {
+    let mut timers = Timers::default();
+    assert!(timers.start("a", 100, false).is_empty());
+    assert!(timers.start("a", 150, false).is_empty());
+    assert_eq!(
+        timers.start("b", 200, false),
+        [("a".to_string(), Interval { start: 100, end: 200 })]
+    );
+    assert!(timers.start("c", 300, true).is_empty());
+    assert!(timers.is_running("b") && timers.is_running("c"));
+
+    assert_eq!(timers.stop("b", 400), Some(Interval { start: 200, end: 400 }));
+    assert_eq!(timers.stop("b", 500), None);
+    // A clock that went backwards never yields a negative session
+    assert_eq!(timers.stop("c", 250), Some(Interval { start: 300, end: 300 }));
+}
+
+#[test]
+fn running_timers_survive_a_restart() {
+    let paths = temp_vault("timers");
+    assert_eq!(timer::load(&paths), Timers::default());
+
+    let mut timers = Timers::default();
+    timers.start("a", 100, false);
+    timer::save(&paths, &timers).unwrap();
+    assert_eq!(timer::load(&paths), timers);
+}
+
+#[test]
+fn sessions_are_logged_on_the_exact_task() {
+    let mut groups = vec![tasks::parse_group("inbox", INBOX)];
+    timer::log_interval(&mut groups, "a", Interval { start: 5000, end: 6000 }).unwrap();
+    // Logged again after a crash, the longer copy replaces the first one
+    timer::log_interval(&mut groups, "a", Interval { start: 5000, end: 9000 }).unwrap();
+    timer::log_interval(&mut groups, "a", Interval { start: 2000, end: 3000 }).unwrap();
+    assert_eq!(
+        groups[0].tasks[0].intervals,
+        [Interval { start: 2000, end: 3000 }, Interval { start: 5000, end: 9000 }]
+    );
+    assert_eq!(groups[0].tasks[2].intervals, []);
+
+    // With `a` deleted its session must not land on `ab`, whose id it prefixes
+    groups[0].tasks.remove(0);
+    let gone = timer::log_interval(&mut groups, "a", Interval { start: 0, end: 1 }).unwrap_err();
+    assert_eq!(gone.kind, ErrorKind::NotFound);
+    assert_eq!(groups[0].tasks[1].intervals, []);
+}
+
+#[test]
+fn a_save_racing_a_timer_stop_keeps_both_changes() {
+    let paths = temp_vault("timer-race");
+    fs::write(paths.group_file("inbox"), INBOX).unwrap();
+    let other = "# Other\n\n- [ ] c <!-- id:c,created:1,order:0 -->\n";
+    fs::write(paths.group_file("other"), other).unwrap();
+
+    // What the frontend loaded, and its edit on the way to disk
+    let base = store::load_group(&paths, "inbox").unwrap();
+    let mut ours = base.clone();
+    ours.tasks[1].content = "b, renamed".to_string();
+
+    // Meanwhile the timer on `a` stops; only its group is written
+    let mut owners = HashMap::new();
+    let session = Interval { start: 5000, end: 6000 };
+    let changed = timer::log_sessions(&paths, &[("a".to_string(), session)], &mut owners);
+    assert_eq!(changed.len(), 1);
+    store::save_groups(&paths, &[&changed[0]]).unwrap();
+    assert_eq!(owners["a"], "inbox");
+    assert_eq!(fs::read_to_string(paths.group_file("other")).unwrap(), other);
+
+    // The save still merges against what the frontend had
+    let theirs = store::load_group(&paths, "inbox").unwrap();
+    let merged = merge_groups(&base, &ours, &theirs).group;
+    assert_eq!(merged.tasks[0].intervals, [session]);
+    assert_eq!(merged.tasks[1].content, "b, renamed");
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+use common::temp_vault;
+use nekotick_lib::usage::{Collector, FocusSource};
+use nekotick_lib::vault::{store, time_log};
+
+// The app "in front", switched by the test
+struct FakeFocus(Rc<RefCell<Option<String>>>);
+
+impl FocusSource for FakeFocus {
+    fn focused_app(&mut self) -> Option<String> {
+        self.0.borrow().clone()
+    }
+}
+
+fn noon() -> i64 {
+    let noon = chrono::NaiveDate::from_ymd_opt(2024, 11, 30)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    noon.and_local_timezone(chrono::Local).unwrap().timestamp_millis()
+}
+
+#[test]
+fn time_between_samples_goes_to_the_app_in_front() {
+    let focus = Rc::new(RefCell::new(Some("firefox".to_string())));
+    let mut collector = Collector::new(Box::new(FakeFocus(focus.clone())));
+    let t = noon();
+
+    collector.sample(t, false);
+    *focus.borrow_mut() = Some("code".to_string());
+    collector.sample(t + 1_500, false);
+    collector.sample(t + 4_000, false);
+    // Nothing focused, then a gap the machine slept through
+    *focus.borrow_mut() = None;
+    collector.sample(t + 5_000, false);
+    *focus.borrow_mut() = Some("code".to_string());
+    collector.sample(t + 6_000, false);
+    collector.sample(t + 600_000, false);
+    // Idle: the second before counts, nothing after it
+    collector.sample(t + 601_000, true);
+    collector.sample(t + 700_000, false);
+
+    let mut days = Vec::new();
+    assert!(collector.drain_into(&mut days));
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].date, "2024-11-30");
+    let apps: Vec<(&str, u64)> = days[0].apps.iter().map(|a| (a.name.as_str(), a.duration)).collect();
+    assert_eq!(apps, [("code", 4), ("firefox", 1)]);
+
+    // Half seconds wait for the next flush, where two make a whole one
+    collector.sample(t + 700_500, false);
+    assert!(collector.drain_into(&mut days));
+    assert_eq!((days[0].apps[0].duration, days[0].apps[1].duration), (5, 1));
+}
+
+#[test]
+fn flushing_adds_to_the_log_on_disk() {
+    let paths = temp_vault("usage");
+    fs::write(
+        paths.time_log_file(),
+        "# 时间记录\n\n## 2024-11-30\n\n### 应用使用时间\n- code: 100秒\n\n### 网站访问时间\n- github.com: 5秒\n",
+    )
+    .unwrap();
+
+    let focus = Rc::new(RefCell::new(Some("code".to_string())));
+    let mut collector = Collector::new(Box::new(FakeFocus(focus.clone())));
+    collector.sample(noon(), false);
+    collector.sample(noon() + 5_000, false);
+    collector.flush(&paths).unwrap();
+
+    let text = fs::read_to_string(paths.time_log_file()).unwrap();
+    assert!(text.contains("## 2024-11-30\n\n### 应用使用时间\n- code: 105秒\n"));
+    let days = store::load_time_log(&paths).unwrap();
+    assert_eq!(days[0].websites[0].duration, 5);
+    assert_eq!(time_log::parse_time_log(&text), days);
+}
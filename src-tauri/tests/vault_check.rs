@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::Path;
+
+use nekotick_lib::vault::{check, tasks, Group, Issue};
+
+fn fixture(name: &str) -> Group {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/groups")
+        .join(name);
+    tasks::parse_group("dup", &fs::read_to_string(path).unwrap())
+}
+
+fn task<'a>(group: &'a Group, content: &str) -> &'a nekotick_lib::vault::Task {
+    group.tasks.iter().find(|t| t.content == content).unwrap()
+}
+
+#[test]
+fn reports_every_kind_without_changing_anything() {
+    let groups = vec![fixture("duplicate_ids.md")];
+    let before = groups.clone();
+    let issues = check::check(&groups);
+    assert_eq!(groups, before);
+
+    assert_eq!(
+        issues,
+        vec![
+            Issue::DuplicateId {
+                group_id: "dup".into(),
+                task_id: "dup".into(),
+                new_id: "dup-2".into(),
+            },
+            Issue::DanglingParent {
+                group_id: "dup".into(),
+                task_id: "orphan".into(),
+                parent_id: "gone".into(),
+            },
+            Issue::ParentCycle {
+                group_id: "dup".into(),
+                task_ids: vec!["loop-a".into(), "loop-b".into()],
+                cut: "loop-a".into(),
+            },
+            // loop-b is now loop-a's only child but kept its top-level order
+            Issue::OrderOutOfRange {
+                group_id: "dup".into(),
+                parent_id: Some("loop-a".into()),
+                orders: vec![4],
+            },
+        ]
+    );
+}
+
+#[test]
+fn repair_is_deterministic_and_complete() {
+    let mut first = vec![fixture("duplicate_ids.md")];
+    let mut second = first.clone();
+    assert_eq!(check::repair(&mut first), check::repair(&mut second));
+    assert_eq!(first, second);
+    assert!(check::check(&first).is_empty());
+
+    let group = &first[0];
+    assert_eq!(task(group, "Second copy").id, "dup-2");
+    assert_eq!(task(group, "Child of the duplicate").parent_id.as_deref(), Some("dup"));
+    assert_eq!(task(group, "Orphan whose parent was deleted").parent_id, None);
+    assert_eq!(task(group, "Loop A").parent_id, None);
+    assert_eq!(task(group, "Loop B").parent_id.as_deref(), Some("loop-a"));
+
+    // Nothing is lost on the way to disk
+    let text = tasks::serialize_group(group);
+    assert_eq!(tasks::parse_group("dup", &text).tasks.len(), group.tasks.len());
+}
+
+#[test]
+fn duplicates_across_groups_take_their_children_along() {
+    let a = tasks::parse_group("a", "# A\n\n- [ ] one <!--id:x,created:1,order:0-->");
+    let b = tasks::parse_group(
+        "b",
+        "# B\n\n- [ ] two <!--id:x,created:1,order:0-->\n  - [ ] kid <!--id:k,created:1,order:0,parent:x-->\n- [ ] taken <!--id:x-2,created:1,order:1-->",
+    );
+    let mut groups = vec![a, b];
+    let issues = check::repair(&mut groups);
+
+    assert_eq!(
+        issues,
+        vec![Issue::DuplicateId {
+            group_id: "b".into(),
+            task_id: "x".into(),
+            new_id: "x-3".into(),
+        }]
+    );
+    assert_eq!(task(&groups[0], "one").id, "x");
+    assert_eq!(task(&groups[1], "two").id, "x-3");
+    assert_eq!(task(&groups[1], "kid").parent_id.as_deref(), Some("x-3"));
+}
+
+#[test]
+fn healthy_groups_have_no_issues() {
+    for name in ["nested.md", "cjk.md", "crlf.md"] {
+        assert!(check::check(&[fixture(name)]).is_empty(), "{}", name);
+    }
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::temp_vault;
+use nekotick_lib::vault::{store, Settings};
+use nekotick_lib::web::{domain, Endpoint, WebTracker};
+
+// The status code the listener answers a heartbeat with
+fn beat(endpoint: &Endpoint, host: &str, token: &str, body: &str) -> u16 {
+    let mut stream = TcpStream::connect(("127.0.0.1", endpoint.port)).unwrap();
+    write!(
+        stream,
+        "POST /heartbeat HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        host,
+        token,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.split_whitespace().nth(1).unwrap().parse().unwrap()
+}
+
+#[test]
+fn domains_are_checked_and_lowercased() {
+    assert_eq!(domain(" GitHub.com. "), Some("github.com".to_string()));
+    assert_eq!(domain("localhost:5173"), Some("localhost:5173".to_string()));
+    assert_eq!(domain(""), None);
+    assert_eq!(domain("evil.com\n- x: 9999秒"), None);
+    assert_eq!(domain(&"a".repeat(254)), None);
+}
+
+#[test]
+fn heartbeats_with_the_token_are_logged() {
+    let paths = temp_vault("web");
+    let web = WebTracker::default();
+    assert_eq!(web.endpoint(), None);
+
+    let settings = Settings {
+        track_websites: true,
+        ..Settings::default()
+    };
+    web.apply(&paths, &settings).unwrap();
+    let endpoint: Endpoint = serde_json::from_slice(&fs::read(paths.extension_file()).unwrap()).unwrap();
+    assert_eq!(web.endpoint(), Some(endpoint.clone()));
+    let host = format!("127.0.0.1:{}", endpoint.port);
+
+    assert_eq!(beat(&endpoint, &host, "wrong", r#"{"domain":"github.com"}"#), 401);
+    assert_eq!(
+        beat(
+            &endpoint,
+            "attacker.example",
+            &endpoint.token,
+            r#"{"domain":"github.com"}"#
+        ),
+        403
+    );
+    assert_eq!(
+        beat(&endpoint, &host, &endpoint.token, r#"{"domain":"bad domain"}"#),
+        400
+    );
+
+    assert_eq!(
+        beat(&endpoint, &host, &endpoint.token, r#"{"domain":"GitHub.com"}"#),
+        204
+    );
+    thread::sleep(Duration::from_millis(1_100));
+    assert_eq!(beat(&endpoint, &host, &endpoint.token, r#"{"domain":null}"#), 204);
+    // Kept in memory until the next flush
+    assert!(store::load_time_log(&paths).unwrap().is_empty());
+
+    // Turning tracking off writes the time, closes the port and removes the token
+    web.apply(&paths, &Settings::default()).unwrap();
+    assert_eq!(web.endpoint(), None);
+    assert!(!paths.extension_file().exists());
+    let days = store::load_time_log(&paths).unwrap();
+    let total: u64 = days
+        .iter()
+        .flat_map(|d| &d.websites)
+        .filter(|w| w.name == "github.com")
+        .map(|w| w.duration)
+        .sum();
+    assert_eq!(total, 1);
+}
+
+#[test]
+fn a_stalled_connection_holds_up_nobody_and_pages_get_no_cors() {
+    let paths = temp_vault("web-stall");
+    let web = WebTracker::default();
+    let endpoint = web.start(&paths).unwrap();
+    let host = format!("127.0.0.1:{}", endpoint.port);
+    assert_eq!(endpoint.token.len(), 32);
+
+    // Connected but never sends a byte
+    let _stalled = TcpStream::connect(("127.0.0.1", endpoint.port)).unwrap();
+    let started = Instant::now();
+    assert_eq!(beat(&endpoint, &host, &endpoint.token, r#"{"domain":null}"#), 204);
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    let mut stream = TcpStream::connect(("127.0.0.1", endpoint.port)).unwrap();
+    write!(
+        stream,
+        "OPTIONS /heartbeat HTTP/1.1\r\nHost: {}\r\nOrigin: https://attacker.example\r\n\r\n",
+        host
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 405"), "{}", response);
+    assert!(
+        !response.to_ascii_lowercase().contains("access-control"),
+        "{}",
+        response
+    );
+    web.stop(&paths).unwrap();
+}
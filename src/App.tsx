@@ -3,6 +3,7 @@ import { MoreHorizontal, Check } from 'lucide-react';
 import { TaskList } from '@/components/features/TaskList';
 import { TaskInput } from '@/components/features/TaskInput';
 import { CommandMenu } from '@/components/features/CommandMenu';
+import { IdleDialog } from '@/components/features/IdleDialog';
 import { GroupSidebar } from '@/components/features/GroupDrawer';
 import { TimeTrackerPage } from '@/components/TimeTracker';
 import { ProgressPage } from '@/components/Progress';
@@ -11,6 +12,11 @@ import { ThemeProvider } from '@/components/theme-provider';
 import { ToastContainer } from '@/components/ui/Toast';
 import { useViewStore } from '@/stores/useViewStore';
 import { useGroupStore } from '@/stores/useGroupStore';
+import { useTimerStore } from '@/stores/useTimerStore';
+import { getCurrentWebview } from '@tauri-apps/api/webview';
+import { onGroupChanged, onGroupRemoved, importFiles, adoptGroup } from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
+import { useToastStore } from '@/stores/useToastStore';
 import { useVimShortcuts } from '@/hooks/useVimShortcuts';
 import { useShortcuts } from '@/hooks/useShortcuts';
 
@@ -18,7 +24,7 @@ function AppContent() {
   // Enable shortcuts
   useShortcuts();
   const { currentView } = useViewStore();
-  const { activeGroupId, deleteGroup, groups, tasks, loadData, loaded, hideCompleted, setHideCompleted } = useGroupStore();
+  const { activeGroupId, deleteGroup, groups, tasks, loadData, loaded, hideCompleted, setHideCompleted, applyExternalGroup, removeExternalGroup } = useGroupStore();
   const [showMoreMenu, setShowMoreMenu] = useState(false);
   const [showInfoModal, setShowInfoModal] = useState(false);
   const moreMenuRef = useRef<HTMLDivElement>(null);
@@ -53,6 +59,49 @@ function AppContent() {
     loadData();
   }, [loadData]);
 
+  // Task timers run in the backend; resume the display and follow its ticks
+  useEffect(() => {
+    const cleanup = useTimerStore.getState().init();
+    return () => {
+      cleanup.then((fn) => fn());
+    };
+  }, []);
+
+  // Pick up edits made to tasks/*.md outside the app (editors, Syncthing)
+  useEffect(() => {
+    const unlisteners = [
+      onGroupChanged((group) => {
+        applyExternalGroup(group);
+        adoptGroup(group);
+      }),
+      onGroupRemoved(removeExternalGroup),
+    ];
+    return () => {
+      unlisteners.forEach((p) => p.then((unlisten) => unlisten()));
+    };
+  }, [applyExternalGroup, removeExternalGroup]);
+
+  // Dropping .md/.txt checklists onto the window imports them into the active group
+  useEffect(() => {
+    const unlisten = getCurrentWebview().onDragDropEvent(async (event) => {
+      if (event.payload.type !== 'drop') return;
+      const files = event.payload.paths.filter((p) => /\.(md|markdown|txt)$/i.test(p));
+      if (files.length === 0) return;
+      const { addToast } = useToastStore.getState();
+      try {
+        const before = useGroupStore.getState().tasks.length;
+        const group = await importFiles(useGroupStore.getState().activeGroupId, files);
+        applyExternalGroup(group);
+        addToast(`已导入 ${useGroupStore.getState().tasks.length - before} 个任务`, 'success');
+      } catch (e) {
+        addToast(`导入失败：${errorMessage(e)}`, 'error', 4000);
+      }
+    });
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, [applyExternalGroup]);
+
   const handleFocusInput = () => {
     // Focus the task input (now using textarea)
     const input = document.querySelector<HTMLTextAreaElement>(
@@ -217,6 +266,7 @@ function App() {
   return (
     <ThemeProvider>
       <AppContent />
+      <IdleDialog />
       <ToastContainer />
     </ThemeProvider>
   );
@@ -21,6 +21,7 @@ import { CSS } from '@dnd-kit/utilities';
 import { invoke } from '@tauri-apps/api/core';
 import { ChevronLeft, Plus, Minus, GripVertical } from 'lucide-react';
 import { useProgressStore, type ProgressItem, type CounterItem } from '@/stores/useProgressStore';
+import { loadTimeTracker } from '@/lib/storage';
 
 type ViewMode = 'list' | 'create-progress' | 'create-counter';
 
@@ -33,17 +34,23 @@ const animateLayoutChanges: AnimateLayoutChanges = (args) => {
   return defaultAnimateLayoutChanges(args);
 };
 
-// Update position without awaiting result for smoother animation
-const updatePositionFast = (x: number, y: number) => {
-  invoke('update_drag_window_position', { x, y }).catch(() => {});
-};
-
 export function ProgressPage() {
   const { items, addProgress, addCounter, updateCurrent, deleteItem, loadItems, reorderItems } = useProgressStore();
   
   useEffect(() => {
     loadItems();
   }, [loadItems]);
+
+  // 今天完成的番茄数（记录在 time-log.md，按本地日期）
+  const [todayPomodoros, setTodayPomodoros] = useState(0);
+  useEffect(() => {
+    const now = new Date();
+    const pad = (n: number) => String(n).padStart(2, '0');
+    const today = `${now.getFullYear()}-${pad(now.getMonth() + 1)}-${pad(now.getDate())}`;
+    loadTimeTracker().then((days) => {
+      setTodayPomodoros(days.find((d) => d.date === today)?.pomodoros ?? 0);
+    });
+  }, []);
   const [viewMode, setViewMode] = useState<ViewMode>('list');
   const [showFabMenu, setShowFabMenu] = useState(false);
   const [activeId, setActiveId] = useState<string | null>(null);
@@ -110,10 +117,8 @@ export function ProgressPage() {
     if (item) {
       const itemElement = document.querySelector(`[data-item-id="${id}"]`);
       const rect = itemElement?.getBoundingClientRect();
-      const width = rect?.width || 350;
-      const height = rect?.height || 80;
+      const width = rect?.width;
       
-      const pointer = (event.activatorEvent as PointerEvent);
       const isDarkMode = document.documentElement.classList.contains('dark');
       
       // 构建更详细的显示内容
@@ -126,31 +131,17 @@ export function ProgressPage() {
       }
       
       try {
-        await invoke('create_drag_window', {
-          content: displayContent,
-          x: pointer.screenX,
-          y: pointer.screenY,
-          width: width,
-          height: height,
-          isDone: false,
+        await invoke('show_drag_overlay', {
+          items: [{ content: displayContent, done: false, priority: 'default', depth: 0 }],
+          width,
           isDark: isDarkMode,
-          priority: 'default',
         });
       } catch (e) {
-        console.error('Failed to create drag window:', e);
+        console.error('Failed to show drag overlay:', e);
       }
     }
   }, [items]);
 
-  const handleDragMove = useCallback((event: DragMoveEvent) => {
-    if (!event.activatorEvent) return;
-    const rect = event.activatorEvent as PointerEvent;
-    // Calculate current position with delta
-    const x = rect.screenX + (event.delta?.x || 0);
-    const y = rect.screenY + (event.delta?.y || 0);
-    updatePositionFast(x, y);
-  }, []);
-
   const handleDragOver = useCallback((event: DragMoveEvent) => {
     setOverId(event.over?.id as string | null);
   }, []);
@@ -162,7 +153,7 @@ export function ProgressPage() {
     setOverId(null);
     
     try {
-      await invoke('destroy_drag_window');
+      await invoke('hide_drag_overlay');
     } catch (e) {
       // ignore
     }
@@ -174,7 +165,7 @@ export function ProgressPage() {
   
   useEffect(() => {
     return () => {
-      invoke('destroy_drag_window').catch(() => {});
+      invoke('hide_drag_overlay').catch(() => {});
     };
   }, []);
 
@@ -363,6 +354,9 @@ export function ProgressPage() {
     <div className="h-full bg-white dark:bg-zinc-900 flex flex-col pt-2 relative">
       {/* Content */}
       <div className="flex-1 overflow-y-auto px-6 py-4">
+        {todayPomodoros > 0 && (
+          <p className="text-xs text-zinc-400 mb-3">今天完成 {todayPomodoros} 个番茄</p>
+        )}
         {items.length === 0 && (
           <p className="text-sm text-zinc-300 text-center py-12">暂无进度</p>
         )}
@@ -370,7 +364,6 @@ export function ProgressPage() {
           sensors={sensors}
           collisionDetection={closestCenter}
           onDragStart={handleDragStart}
-          onDragMove={handleDragMove}
           onDragOver={handleDragOver}
           onDragEnd={handleDragEnd}
         >
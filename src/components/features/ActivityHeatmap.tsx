@@ -1,7 +1,8 @@
-import { useMemo } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import { ActivityCalendar, type ThemeInput } from 'react-activity-calendar';
 import { Tooltip } from 'react-tooltip';
 import { useGroupStore } from '@/stores/useGroupStore';
+import { loadTimeTracker } from '@/lib/storage';
 
 // Zinc-based monochromatic theme
 const calendarTheme: ThemeInput = {
@@ -61,8 +62,24 @@ function useActivityData(): ActivityData[] {
   }, [tasks]);
 }
 
+/**
+ * Pomodoros finished per day, from the time log
+ */
+function usePomodoroCounts(): Map<string, number> {
+  const [counts, setCounts] = useState<Map<string, number>>(new Map());
+
+  useEffect(() => {
+    loadTimeTracker().then((days) => {
+      setCounts(new Map(days.filter((d) => d.pomodoros > 0).map((d) => [d.date, d.pomodoros])));
+    });
+  }, []);
+
+  return counts;
+}
+
 export function ActivityHeatmap() {
   const data = useActivityData();
+  const pomodoros = usePomodoroCounts();
   const tasks = useGroupStore((state) => state.tasks);
 
   // Calculate stats
@@ -91,9 +108,10 @@ export function ActivityHeatmap() {
   return (
     <div className="space-y-6">
       {/* Stats Summary */}
-      <div className="grid grid-cols-2 gap-4">
+      <div className="grid grid-cols-3 gap-4">
         <StatCard label="Total Completed" value={totalCompleted} />
         <StatCard label="Current Streak" value={`${currentStreak} days`} />
+        <StatCard label="Pomodoros" value={[...pomodoros.values()].reduce((sum, n) => sum + n, 0)} />
       </div>
 
       {/* Heatmap */}
@@ -109,7 +127,9 @@ export function ActivityHeatmap() {
           renderBlock={(block, activity) => (
             <g 
               data-tooltip-id="activity-tooltip" 
-              data-tooltip-content={`${activity.count} task${activity.count !== 1 ? 's' : ''} on ${activity.date}`}
+              data-tooltip-content={`${activity.count} task${activity.count !== 1 ? 's' : ''}${
+                pomodoros.has(activity.date) ? `, ${pomodoros.get(activity.date)} pomodoros` : ''
+              } on ${activity.date}`}
             >
               {block}
             </g>
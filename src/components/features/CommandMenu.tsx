@@ -17,8 +17,13 @@ import {
   Trash2,
   Settings,
   Keyboard,
+  LifeBuoy,
+  FileInput,
 } from 'lucide-react';
 import { useGroupStore } from '@/stores/useGroupStore';
+import { useToastStore } from '@/stores/useToastStore';
+import { collectDiagnostics, migrateLegacy } from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
 
 interface CommandMenuProps {
   onFocusInput?: () => void;
@@ -54,6 +59,27 @@ export function CommandMenu({ onFocusInput }: CommandMenuProps) {
     completedTasks.forEach((t) => deleteTask(t.id));
   };
 
+  const handleCollectDiagnostics = () => {
+    const { addToast } = useToastStore.getState();
+    collectDiagnostics()
+      .then((path) => addToast(`诊断信息已保存到 ${path}（含日志、版本和目录结构，分组名与数据路径已隐去；发送前可解压查看）`, 'success', 8000))
+      .catch((e) => addToast(`收集诊断信息失败：${errorMessage(e)}`, 'error', 4000));
+  };
+
+  const handleMigrateLegacy = () => {
+    const { addToast } = useToastStore.getState();
+    migrateLegacy()
+      .then(({ group, imported, skipped }) => {
+        if (group) {
+          useGroupStore.getState().applyExternalGroup(group);
+          addToast(`已导入 ${imported} 个任务到「${group.name}」`, 'success');
+        } else {
+          addToast(`没有需要导入的任务（${skipped} 个已存在）`, 'success');
+        }
+      })
+      .catch((e) => addToast(`导入旧版数据失败：${errorMessage(e)}`, 'error', 4000));
+  };
+
   const completedCount = tasks.filter((t) => t.completed).length;
 
   return (
@@ -141,6 +167,26 @@ export function CommandMenu({ onFocusInput }: CommandMenuProps) {
               Coming soon
             </span>
           </CommandItem>
+          <CommandItem
+            onSelect={() => runCommand(handleCollectDiagnostics)}
+            className="gap-2"
+          >
+            <LifeBuoy className="h-4 w-4" />
+            <span>Collect Diagnostics</span>
+            <span className="ml-auto text-xs text-muted-foreground">
+              For bug reports
+            </span>
+          </CommandItem>
+          <CommandItem
+            onSelect={() => runCommand(handleMigrateLegacy)}
+            className="gap-2"
+          >
+            <FileInput className="h-4 w-4" />
+            <span>Import Legacy nekotick.md</span>
+            <span className="ml-auto text-xs text-muted-foreground">
+              Documents/NekoTick
+            </span>
+          </CommandItem>
           <CommandItem
             disabled
             className="gap-2 opacity-50"
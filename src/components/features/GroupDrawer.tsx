@@ -243,6 +243,7 @@ export function GroupSidebar() {
     reorderGroups,
     draggingTaskId,
     moveTaskToGroup,
+    moveTasksToGroup,
     setSearchQuery: setGlobalSearchQuery,
   } = useGroupStore();
   
@@ -601,7 +602,14 @@ export function GroupSidebar() {
                       const taskId = draggingTaskId || cachedDraggingTaskIdRef.current;
                       const originalGroupId = originalGroupIdRef.current;
                       if (taskId && originalGroupId && group.id !== originalGroupId) {
-                        moveTaskToGroup(taskId, group.id);
+                        const { tasks, selectedTaskIds, activeGroupId: current } = useGroupStore.getState();
+                        const selection = selectedTaskIds.filter(id => tasks.some(t => t.id === id));
+                        if (selection.length > 1 && selection.includes(taskId)) {
+                          // 悬停已切换到这个分组时由 TaskList 移动，避免移动两次
+                          if (group.id !== current) moveTasksToGroup(selection, group.id);
+                        } else {
+                          moveTaskToGroup(taskId, group.id);
+                        }
                       }
                     }}
                   />
@@ -0,0 +1,80 @@
+import { useState } from 'react';
+import { motion, AnimatePresence } from 'framer-motion';
+import { useTimerStore } from '@/stores/useTimerStore';
+import { useGroupStore } from '@/stores/useGroupStore';
+
+// 空闲期间计时已暂停；回来后询问这段时间怎么算，选择前计时保持暂停
+export function IdleDialog() {
+  const idle = useTimerStore((s) => s.idle);
+  const resolveIdle = useTimerStore((s) => s.resolveIdle);
+  const tasks = useGroupStore((s) => s.tasks);
+  const [reassignTo, setReassignTo] = useState('');
+
+  const returnedAt = idle?.returnedAt;
+  const open = Boolean(idle && returnedAt);
+  const minutes = idle && returnedAt ? Math.round((returnedAt - idle.since) / 60000) : 0;
+  const paused = tasks.filter((t) => idle?.tasks.includes(t.id));
+  const others = tasks.filter((t) => !t.completed && !idle?.tasks.includes(t.id));
+
+  const buttonClass =
+    'w-full py-2 text-sm text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700 rounded hover:bg-zinc-50 dark:hover:bg-zinc-800 disabled:opacity-30 disabled:cursor-not-allowed transition-colors';
+
+  return (
+    <AnimatePresence>
+      {open && (
+        <motion.div
+          initial={{ opacity: 0 }}
+          animate={{ opacity: 1 }}
+          exit={{ opacity: 0 }}
+          className="fixed inset-0 bg-black/20 dark:bg-black/40 flex items-center justify-center z-50"
+        >
+          <motion.div
+            initial={{ scale: 0.95, opacity: 0 }}
+            animate={{ scale: 1, opacity: 1 }}
+            exit={{ scale: 0.95, opacity: 0 }}
+            className="bg-white dark:bg-zinc-900 rounded-lg shadow-xl w-[360px] max-w-[90vw] p-4 space-y-3"
+          >
+            <p className="text-sm text-zinc-700 dark:text-zinc-300">
+              你离开了 {minutes} 分钟，计时已暂停
+            </p>
+            {paused.length > 0 && (
+              <p className="text-xs text-zinc-400 truncate">
+                {paused.map((t) => t.content).join('、')}
+              </p>
+            )}
+            <button onClick={() => resolveIdle({ action: 'keep' })} className={buttonClass}>
+              保留，算作工作时间
+            </button>
+            <button onClick={() => resolveIdle({ action: 'discard' })} className={buttonClass}>
+              丢弃这段时间
+            </button>
+            <div className="flex gap-2">
+              <select
+                value={reassignTo}
+                onChange={(e) => setReassignTo(e.target.value)}
+                className="flex-1 min-w-0 px-2 py-1 text-sm bg-transparent border-b border-zinc-200 dark:border-zinc-700 outline-none"
+              >
+                <option value="">选择任务…</option>
+                {others.map((t) => (
+                  <option key={t.id} value={t.id}>
+                    {t.content}
+                  </option>
+                ))}
+              </select>
+              <button
+                onClick={() => {
+                  resolveIdle({ action: 'reassign', taskId: reassignTo });
+                  setReassignTo('');
+                }}
+                disabled={!reassignTo}
+                className="px-3 py-1 text-sm text-zinc-600 dark:text-zinc-300 border border-zinc-200 dark:border-zinc-700 rounded hover:bg-zinc-50 dark:hover:bg-zinc-800 disabled:opacity-30 disabled:cursor-not-allowed transition-colors"
+              >
+                记到该任务
+              </button>
+            </div>
+          </motion.div>
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+}
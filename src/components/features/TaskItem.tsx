@@ -2,10 +2,11 @@ import { useState, useRef, useEffect } from 'react';
 import { useSortable, defaultAnimateLayoutChanges, type AnimateLayoutChanges } from '@dnd-kit/sortable';
 import { CSS } from '@dnd-kit/utilities';
 import { Checkbox } from '@/components/ui/checkbox';
-import { GripVertical, MoreHorizontal, Trash2, ChevronRight, ChevronDown, Plus } from 'lucide-react';
+import { GripVertical, MoreHorizontal, Trash2, ChevronRight, ChevronDown, Plus, Play, Square, Timer, Coffee } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import type { Task } from '@/types';
 import { useGroupStore } from '@/stores/useGroupStore';
+import { useTimerStore, formatElapsed } from '@/stores/useTimerStore';
 
 // Disable drop animation to prevent "snap back" effect
 const animateLayoutChanges: AnimateLayoutChanges = (args) => {
@@ -30,9 +31,11 @@ interface TaskItemProps {
   hasChildren?: boolean;
   collapsed?: boolean;
   onToggleCollapse?: () => void;
+  selected?: boolean;
+  onSelect?: (id: string) => void;
 }
 
-export function TaskItem({ task, onToggle, onUpdate, onDelete, onAddSubTask, isBeingDragged, isDropTarget, insertAfter, level = 0, hasChildren = false, collapsed = false, onToggleCollapse }: TaskItemProps) {
+export function TaskItem({ task, onToggle, onUpdate, onDelete, onAddSubTask, isBeingDragged, isDropTarget, insertAfter, level = 0, hasChildren = false, collapsed = false, onToggleCollapse, selected = false, onSelect }: TaskItemProps) {
   const MAX_LEVEL = 3; // 0, 1, 2, 3 = 4 层
   const canAddSubTask = level < MAX_LEVEL;
   const itemRef = useRef<HTMLDivElement>(null);
@@ -42,6 +45,9 @@ export function TaskItem({ task, onToggle, onUpdate, onDelete, onAddSubTask, isB
   const inputRef = useRef<HTMLTextAreaElement>(null);
   const menuRef = useRef<HTMLDivElement>(null);
   const { searchQuery } = useGroupStore();
+  const timer = useTimerStore((s) => s.running.find((t) => t.taskId === task.id));
+  const timerNow = useTimerStore((s) => s.now);
+  const pomodoro = useTimerStore((s) => (s.pomodoro?.session?.taskId === task.id ? s.pomodoro.session : null));
   
   // 高亮搜索词 (加粗)
   const highlightText = (text: string, query: string) => {
@@ -145,12 +151,22 @@ export function TaskItem({ task, onToggle, onUpdate, onDelete, onAddSubTask, isB
         ref={combinedRef}
         style={{ ...style, transition: 'none' }}
         data-task-id={task.id}
+        onClickCapture={(e) => {
+          // Ctrl/Cmd+click selects instead of editing or toggling
+          if (onSelect && (e.ctrlKey || e.metaKey)) {
+            e.preventDefault();
+            e.stopPropagation();
+            onSelect(task.id);
+          }
+        }}
         className={cn(
           'group flex items-start gap-2 px-2 py-2 rounded-md',
           'border border-transparent',
           isBeingDragged 
             ? 'h-0 overflow-hidden opacity-0 !p-0 !m-0' 
-            : 'hover:bg-muted/50 hover:border-border/50'
+            : selected
+              ? 'bg-muted border-border'
+              : 'hover:bg-muted/50 hover:border-border/50'
         )}
       >
       {/* Collapse/Expand Icon */}
@@ -253,6 +269,45 @@ export function TaskItem({ task, onToggle, onUpdate, onDelete, onAddSubTask, isB
         )}
       </div>
 
+      {/* Pomodoro countdown, in place of the timer while one runs on this task */}
+      {!task.isDone && pomodoro && (
+        <button
+          onClick={() => useTimerStore.getState().stopPomodoro()}
+          className="p-1 rounded hover:bg-muted flex items-center gap-1 text-xs tabular-nums text-foreground"
+          aria-label="Stop pomodoro"
+          title={pomodoro.phase === 'work' ? '放弃这个番茄' : '结束休息'}
+        >
+          {pomodoro.phase === 'work' ? <Timer className="h-3.5 w-3.5" /> : <Coffee className="h-3.5 w-3.5" />}
+          {formatElapsed(pomodoro.endsAt - timerNow)}
+        </button>
+      )}
+
+      {/* Timer */}
+      {!task.isDone && !pomodoro && (
+        <button
+          onClick={() => {
+            const { start, stop } = useTimerStore.getState();
+            if (timer) stop(task.id);
+            else start(task.id);
+          }}
+          className={cn(
+            'p-1 rounded hover:bg-muted flex items-center gap-1 text-xs tabular-nums',
+            timer ? 'text-foreground' : 'opacity-0 group-hover:opacity-100 transition-opacity duration-150'
+          )}
+          aria-label={timer ? 'Stop timer' : 'Start timer'}
+          title={timer ? '停止计时' : '开始计时'}
+        >
+          {timer ? (
+            <>
+              <Square className="h-3.5 w-3.5" />
+              {formatElapsed(timerNow - timer.startedAt)}
+            </>
+          ) : (
+            <Play className="h-4 w-4 text-muted-foreground" />
+          )}
+        </button>
+      )}
+
       {/* More Options Button */}
       <div className="relative" ref={menuRef}>
         <button
@@ -323,6 +378,20 @@ export function TaskItem({ task, onToggle, onUpdate, onDelete, onAddSubTask, isB
               <span>Add Subtask</span>
               {!canAddSubTask && <span className="ml-auto text-xs">(Max 4层)</span>}
             </button>
+            {!task.isDone && (
+              <button
+                onClick={() => {
+                  const { startPomodoro, stopPomodoro } = useTimerStore.getState();
+                  if (pomodoro) stopPomodoro();
+                  else startPomodoro(task.id);
+                  setShowMenu(false);
+                }}
+                className="w-full px-3 py-1.5 text-left text-sm text-zinc-600 dark:text-zinc-300 hover:bg-zinc-50 dark:hover:bg-zinc-800 flex items-center gap-2"
+              >
+                <Timer className="h-4 w-4" />
+                <span>{pomodoro ? 'Stop Pomodoro' : 'Start Pomodoro'}</span>
+              </button>
+            )}
             <div className="h-px bg-zinc-200 dark:bg-zinc-700 my-1" />
             <button
               onClick={() => {
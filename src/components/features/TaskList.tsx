@@ -8,7 +8,6 @@ import {
   useSensors,
   type DragEndEvent,
   type DragStartEvent,
-  type DragMoveEvent,
   type DragOverEvent,
 } from '@dnd-kit/core';
 import { invoke } from '@tauri-apps/api/core';
@@ -22,11 +21,9 @@ import {
 } from '@dnd-kit/sortable';
 import { TaskItem } from './TaskItem';
 import { useGroupStore, type Priority } from '@/stores/useGroupStore';
-
-// Update position without awaiting result for smoother animation
-const updatePositionFast = (x: number, y: number) => {
-  invoke('update_drag_window_position', { x, y }).catch(() => {});
-};
+import { useToastStore } from '@/stores/useToastStore';
+import { exportDrag } from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
 
 export function TaskList() {
   const {
@@ -39,13 +36,19 @@ export function TaskList() {
     activeGroupId,
     updateTaskPriority,
     moveTaskToGroup,
+    moveTasksToGroup,
     addSubTask,
     toggleCollapse,
     hideCompleted,
     searchQuery,
     setDraggingTaskId,
+    selectedTaskIds,
+    toggleTaskSelection,
+    clearTaskSelection,
   } = useGroupStore();
   const [activeId, setActiveId] = useState<string | null>(null);
+  // Everything being dragged: the selection, or just the grabbed task
+  const [draggedIds, setDraggedIds] = useState<string[]>([]);
   const [overId, setOverId] = useState<string | null>(null);
   const [completedExpanded, setCompletedExpanded] = useState(true);
   const [addingSubTaskFor, setAddingSubTaskFor] = useState<string | null>(null);
@@ -56,6 +59,16 @@ export function TaskList() {
   const [showCompletedMenu, setShowCompletedMenu] = useState(false);
   const completedMenuRef = useRef<HTMLDivElement>(null);
 
+  // Esc 清除多选
+  useEffect(() => {
+    if (selectedTaskIds.length === 0) return;
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key === 'Escape') clearTaskSelection();
+    };
+    document.addEventListener('keydown', handleKeyDown);
+    return () => document.removeEventListener('keydown', handleKeyDown);
+  }, [selectedTaskIds, clearTaskSelection]);
+
   // Close completed menu when clicking outside
   useEffect(() => {
     const handleClickOutside = (event: MouseEvent) => {
@@ -200,55 +213,54 @@ export function TaskList() {
     // Save original group for cross-group move detection
     originalGroupIdRef.current = activeGroupId;
     
+    // Match the width of the task element; the height follows the rows
+    const taskElement = document.querySelector(`[data-task-id="${id}"]`);
+    const width = taskElement?.getBoundingClientRect().width;
+    
+    // The overlay follows the cursor on its own; only the theme is needed
+    const isDarkMode = document.documentElement.classList.contains('dark');
+    
+    // Grabbing a selected task drags the whole selection; the backend reads it from the files
+    const selection = selectedTaskIds.filter(s => tasks.some(t => t.id === s));
+    if (selection.length > 1 && selection.includes(id)) {
+      setDraggedIds(selection);
+      try {
+        await invoke('show_selection_drag_overlay', {
+          taskIds: selection,
+          width,
+          isDark: isDarkMode,
+        });
+      } catch (e) {
+        console.error('Failed to show drag overlay:', e);
+      }
+      return;
+    }
+    setDraggedIds([id]);
+    
     const task = tasks.find(t => t.id === id);
     if (task) {
-      // Count all descendants recursively (including the task itself in subtree)
-      const countDescendants = (taskId: string): number => {
-        const children = tasks.filter(t => t.parentId === taskId);
-        if (children.length === 0) return 0;
-        return children.length + children.reduce((sum, child) => sum + countDescendants(child.id), 0);
+      // Flatten the dragged subtree in display order; the overlay caps it
+      const items: { content: string; done: boolean; priority: Priority; depth: number }[] = [];
+      const collect = (t: typeof task, depth: number) => {
+        items.push({ content: t.content, done: t.completed, priority: t.priority || 'default', depth });
+        tasks
+          .filter(c => c.parentId === t.id)
+          .sort((a, b) => a.order - b.order)
+          .forEach(c => collect(c, depth + 1));
       };
-      const childCount = countDescendants(task.id);
-      
-      // Get actual dimensions of the task element
-      const taskElement = document.querySelector(`[data-task-id="${id}"]`);
-      const rect = taskElement?.getBoundingClientRect();
-      const width = rect?.width || 350;
-      const height = rect?.height || 36;
-      
-      // Get mouse position (screen coordinates) and detect dark mode
-      const pointer = (event.activatorEvent as PointerEvent);
-      const isDarkMode = document.documentElement.classList.contains('dark');
-      
-      // Add child count indicator if task has children
-      const displayContent = childCount > 0 
-        ? `${task.content} (+${childCount})` 
-        : task.content;
+      collect(task, 0);
       
       try {
-        await invoke('create_drag_window', {
-          content: displayContent,
-          x: pointer.screenX,
-          y: pointer.screenY,
-          width: width,
-          height: height,
-          isDone: task.completed,
+        await invoke('show_drag_overlay', {
+          items,
+          width,
           isDark: isDarkMode,
-          priority: task.priority || 'default',
         });
       } catch (e) {
-        console.error('Failed to create drag window:', e);
+        console.error('Failed to show drag overlay:', e);
       }
     }
-  }, [tasks, activeGroupId, setDraggingTaskId]);
-
-  const handleDragMove = useCallback((event: DragMoveEvent) => {
-    const rect = (event.activatorEvent as PointerEvent);
-    // Calculate current position
-    const x = rect.screenX + (event.delta?.x || 0);
-    const y = rect.screenY + (event.delta?.y || 0);
-    updatePositionFast(x, y);
-  }, []);
+  }, [tasks, activeGroupId, setDraggingTaskId, selectedTaskIds]);
 
   const handleDragOver = useCallback((event: DragOverEvent) => {
     setOverId(event.over?.id as string | null);
@@ -258,12 +270,26 @@ export function TaskList() {
     const { active, over } = event;
     const taskId = active.id as string;
     const originalGroupId = originalGroupIdRef.current;
+    const isMulti = draggedIds.length > 1;
+    
+    // Not over any task: if the pointer left the window, the backend exports it
+    if (!over) {
+      exportDrag(isMulti ? draggedIds : [taskId])
+        .then((path) => {
+          if (path) useToastStore.getState().addToast(`已导出到 ${path}`, 'success');
+        })
+        .catch((e) => useToastStore.getState().addToast(`导出失败：${errorMessage(e)}`, 'error', 4000));
+    }
     
     // Check if this is a cross-group move (group changed during drag)
     if (originalGroupId && activeGroupId && originalGroupId !== activeGroupId) {
-      // Move task to the new group at the drop position
-      moveTaskToGroup(taskId, activeGroupId, over?.id as string | null);
-    } else if (over && active.id !== over.id) {
+      // Move task(s) to the new group at the drop position
+      if (isMulti) {
+        moveTasksToGroup(draggedIds, activeGroupId, over?.id as string | null);
+      } else {
+        moveTaskToGroup(taskId, activeGroupId, over?.id as string | null);
+      }
+    } else if (over && active.id !== over.id && !isMulti) {
       // Check if dragging across completion status boundary
       const draggedTask = tasks.find(t => t.id === taskId);
       const targetTask = tasks.find(t => t.id === over.id);
@@ -323,11 +349,12 @@ export function TaskList() {
     // Now safe to show the task (it's already at the new position)
     setActiveId(null);
     setOverId(null);
+    setDraggedIds([]);
     originalGroupIdRef.current = null;
     
-    // Destroy drag window
+    // Hide drag overlay
     try {
-      await invoke('destroy_drag_window');
+      await invoke('hide_drag_overlay');
     } catch (e) {
       // ignore
     }
@@ -336,12 +363,12 @@ export function TaskList() {
     setTimeout(() => {
       setDraggingTaskId(null);
     }, 50);
-  }, [reorderTasks, crossStatusReorder, setDraggingTaskId, activeGroupId, moveTaskToGroup, tasks]);
+  }, [reorderTasks, crossStatusReorder, setDraggingTaskId, activeGroupId, moveTaskToGroup, moveTasksToGroup, draggedIds, tasks]);
 
-  // Cleanup: destroy drag window on unmount
+  // Cleanup: hide drag overlay on unmount
   useEffect(() => {
     return () => {
-      invoke('destroy_drag_window').catch(() => {});
+      invoke('hide_drag_overlay').catch(() => {});
     };
   }, []);
 
@@ -366,7 +393,7 @@ export function TaskList() {
     
     // Check if this task or any ancestor is being dragged
     const checkAncestorDragged = (taskId: string, visited = new Set<string>()): boolean => {
-      if (taskId === activeId) return true;
+      if (taskId === activeId || draggedIds.includes(taskId)) return true;
       if (visited.has(taskId)) return false; // Prevent infinite loop
       visited.add(taskId);
       const t = tasks.find(task => task.id === taskId);
@@ -399,6 +426,8 @@ export function TaskList() {
           hasChildren={hasChildren}
           collapsed={task.collapsed}
           onToggleCollapse={() => toggleCollapse(task.id)}
+          selected={selectedTaskIds.includes(task.id)}
+          onSelect={toggleTaskSelection}
         />
         {/* Render children recursively if not collapsed */}
         {hasChildren && !task.collapsed && (
@@ -416,7 +445,6 @@ export function TaskList() {
         sensors={sensors}
         collisionDetection={closestCenter}
         onDragStart={handleDragStart}
-        onDragMove={handleDragMove}
         onDragOver={handleDragOver}
         onDragEnd={handleDragEnd}
       >
@@ -1,10 +1,13 @@
-import { useState, useRef } from 'react';
+import { useState, useRef, type ReactNode } from 'react';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { openUrl } from '@tauri-apps/plugin-opener';
-import { Minus, Square, X, Menu, Pin, Settings, Keyboard } from 'lucide-react';
+import { Minus, Square, X, Menu, Pin, Settings, Keyboard, FileText, Check, Timer, Coffee, AppWindow, Globe, Link } from 'lucide-react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { useViewStore } from '@/stores/useViewStore';
 import { ShortcutsDialog } from '@/components/features/ShortcutsDialog';
+import { getSettings, saveSettings, getWebEndpoint, type Settings as VaultSettings } from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
+import { useToastStore } from '@/stores/useToastStore';
 
 const appWindow = getCurrentWindow();
 
@@ -17,6 +20,7 @@ export function TitleBar() {
   const [settingsPinned, setSettingsPinned] = useState(false);
   const [shortcutsDialogOpen, setShortcutsDialogOpen] = useState(false);
   const [isPinned, setIsPinned] = useState(false);
+  const [vaultSettings, setVaultSettings] = useState<VaultSettings | null>(null);
   const { currentView, setView } = useViewStore();
   const aboutRef = useRef<HTMLDivElement>(null);
   const settingsRef = useRef<HTMLDivElement>(null);
@@ -28,6 +32,80 @@ export function TitleBar() {
     setIsPinned(newPinned);
   };
 
+  // 打开设置菜单时读取后端设置，保证显示的是当前值
+  const openSettings = () => {
+    setSettingsOpen(true);
+    getSettings()
+      .then(setVaultSettings)
+      .catch((error) => console.error('Failed to load settings:', error));
+  };
+
+  // 保存改动后的后端设置；成功后返回新设置，失败时提示并返回 null
+  const updateSettings = async (patch: Partial<VaultSettings>) => {
+    if (!vaultSettings) return null;
+    const next = { ...vaultSettings, ...patch };
+    try {
+      await saveSettings(next);
+      setVaultSettings(next);
+      return next;
+    } catch (error) {
+      console.error('Failed to save settings:', error);
+      useToastStore.getState().addToast(`保存设置失败：${errorMessage(error)}`, 'error', 4000);
+      return null;
+    }
+  };
+
+  // 切换后后端会立即按新格式重写所有分组文件
+  const toggleCleanMarkdown = () => updateSettings({ cleanMarkdown: !vaultSettings?.cleanMarkdown });
+
+  // 番茄钟时长在几组常用设置间切换（工作/短休息/长休息，分钟），也可直接编辑 settings.json
+  const cyclePomodoro = () => {
+    if (!vaultSettings) return;
+    const presets = [[25, 5, 15], [50, 10, 30], [15, 3, 10]];
+    const { workMinutes, shortBreakMinutes, longBreakMinutes } = vaultSettings.pomodoro;
+    const current = presets.findIndex(
+      ([w, s, l]) => w === workMinutes && s === shortBreakMinutes && l === longBreakMinutes
+    );
+    const [w, s, l] = presets[(current + 1) % presets.length];
+    updateSettings({
+      pomodoro: { ...vaultSettings.pomodoro, workMinutes: w, shortBreakMinutes: s, longBreakMinutes: l },
+    });
+  };
+
+  // 空闲检测：关闭 / 5 / 10 / 15 分钟循环切换
+  const cycleIdleMinutes = () => {
+    if (!vaultSettings) return;
+    const steps = [0, 5, 10, 15];
+    const current = steps.indexOf(vaultSettings.idle.minutes);
+    updateSettings({ idle: { ...vaultSettings.idle, minutes: steps[(current + 1) % steps.length] } });
+  };
+
+  const toggleIdleAutoPause = () => {
+    if (!vaultSettings) return;
+    updateSettings({ idle: { ...vaultSettings.idle, autoPause: !vaultSettings.idle.autoPause } });
+  };
+
+  // 后端最多半分钟后按新设置采集
+  const toggleTrackApps = () => updateSettings({ trackApps: !vaultSettings?.trackApps });
+
+  // 复制给浏览器扩展的设置页；每次开启都会换新端口和令牌
+  const copyWebEndpoint = async () => {
+    try {
+      const endpoint = await getWebEndpoint();
+      if (!endpoint) return;
+      await navigator.clipboard.writeText(JSON.stringify(endpoint));
+      useToastStore.getState().addToast('已复制，粘贴到浏览器扩展的设置中', 'success', 3000);
+    } catch (error) {
+      console.error('Failed to copy the extension endpoint:', error);
+      useToastStore.getState().addToast(`复制失败：${errorMessage(error)}`, 'error', 4000);
+    }
+  };
+
+  const toggleTrackWebsites = async () => {
+    const next = await updateSettings({ trackWebsites: !vaultSettings?.trackWebsites });
+    if (next?.trackWebsites) await copyWebEndpoint();
+  };
+
   const startDrag = async () => {
     await appWindow.startDragging();
   };
@@ -163,7 +241,7 @@ export function TitleBar() {
                 className="relative h-full"
                 onMouseEnter={() => {
                   if (!settingsPinned) {
-                    setSettingsOpen(true);
+                    openSettings();
                   }
                 }}
                 onMouseLeave={() => {
@@ -177,7 +255,11 @@ export function TitleBar() {
                   onClick={() => {
                     const newPinned = !settingsPinned;
                     setSettingsPinned(newPinned);
-                    setSettingsOpen(newPinned);
+                    if (newPinned) {
+                      openSettings();
+                    } else {
+                      setSettingsOpen(false);
+                    }
                   }}
                   className="h-full px-3 text-sm text-zinc-200 hover:text-zinc-400 dark:text-zinc-700 dark:hover:text-zinc-500 transition-colors whitespace-nowrap flex items-center gap-1"
                 >
@@ -194,7 +276,9 @@ export function TitleBar() {
                       e.stopPropagation();
                     }}
                   >
-                    <button
+                    <SettingsItem
+                      icon={<Keyboard className="size-4" />}
+                      label="快捷键"
                       onClick={() => {
                         setShortcutsDialogOpen(true);
                         setSettingsOpen(false);
@@ -204,11 +288,61 @@ export function TitleBar() {
                           setMenuPinned(false);
                         }
                       }}
-                      className="w-full px-3 py-2 text-sm text-zinc-600 dark:text-zinc-300 hover:bg-zinc-100 dark:hover:bg-zinc-800 hover:text-zinc-900 dark:hover:text-zinc-100 text-left flex items-center gap-2"
-                    >
-                      <Keyboard className="size-4" />
-                      快捷键
-                    </button>
+                    />
+                    <SettingsItem
+                      icon={<FileText className="size-4" />}
+                      label="纯净 Markdown"
+                      title="任务行不再附带 id 等注释，元数据保存在 .nekotick/index.json"
+                      onClick={toggleCleanMarkdown}
+                      disabled={!vaultSettings}
+                      checked={vaultSettings?.cleanMarkdown}
+                    />
+                    <SettingsItem
+                      icon={<Timer className="size-4" />}
+                      label="番茄钟"
+                      title="工作 / 短休息 / 长休息（分钟），点击切换；下一个番茄开始时生效"
+                      onClick={cyclePomodoro}
+                      disabled={!vaultSettings}
+                      value={
+                        vaultSettings &&
+                        `${vaultSettings.pomodoro.workMinutes}/${vaultSettings.pomodoro.shortBreakMinutes}/${vaultSettings.pomodoro.longBreakMinutes}`
+                      }
+                    />
+                    <SettingsItem
+                      icon={<Coffee className="size-4" />}
+                      label="空闲暂停"
+                      title="离开这么久后暂停正在运行的计时，点击切换"
+                      onClick={cycleIdleMinutes}
+                      disabled={!vaultSettings}
+                      value={vaultSettings && (vaultSettings.idle.minutes > 0 ? `${vaultSettings.idle.minutes} 分钟` : '关闭')}
+                    />
+                    <SettingsItem
+                      icon={<span className="size-4" />}
+                      label="回来时不询问"
+                      title="回来后直接继续计时并丢弃空闲时间，不再询问"
+                      onClick={toggleIdleAutoPause}
+                      disabled={!vaultSettings || vaultSettings.idle.minutes === 0}
+                      checked={vaultSettings?.idle.autoPause}
+                    />
+                    <SettingsItem
+                      icon={<AppWindow className="size-4" />}
+                      label="记录应用使用时间"
+                      title="记录前台应用的使用时间，显示在时间管理页面"
+                      onClick={toggleTrackApps}
+                      disabled={!vaultSettings}
+                      checked={vaultSettings?.trackApps}
+                    />
+                    <SettingsItem
+                      icon={<Globe className="size-4" />}
+                      label="记录网站访问时间"
+                      title="通过浏览器扩展记录网站访问时间，只接受本机连接"
+                      onClick={toggleTrackWebsites}
+                      disabled={!vaultSettings}
+                      checked={vaultSettings?.trackWebsites}
+                    />
+                    {vaultSettings?.trackWebsites && (
+                      <SettingsItem icon={<Link className="size-4" />} label="复制扩展连接信息" onClick={copyWebEndpoint} />
+                    )}
                   </div>
                 )}
               </div>
@@ -309,3 +443,31 @@ export function TitleBar() {
     </div>
   );
 }
+
+interface SettingsItemProps {
+  icon: ReactNode;
+  label: string;
+  title?: string;
+  onClick: () => void;
+  disabled?: boolean;
+  // 开关项打勾，循环项在右侧显示当前值
+  checked?: boolean;
+  value?: ReactNode;
+}
+
+// 设置下拉菜单中的一项
+function SettingsItem({ icon, label, title, onClick, disabled, checked, value }: SettingsItemProps) {
+  return (
+    <button
+      onClick={onClick}
+      disabled={disabled}
+      title={title}
+      className="w-full px-3 py-2 text-sm text-zinc-600 dark:text-zinc-300 hover:bg-zinc-100 dark:hover:bg-zinc-800 hover:text-zinc-900 dark:hover:text-zinc-100 text-left flex items-center gap-2 whitespace-nowrap disabled:opacity-40"
+    >
+      {icon}
+      {label}
+      {checked && <Check className="size-4 ml-auto" />}
+      {value && <span className="ml-auto text-xs text-zinc-400 tabular-nums">{value}</span>}
+    </button>
+  );
+}
@@ -0,0 +1,43 @@
+// 后端命令返回的结构化错误（对应 Rust 端的 NekoError）
+export type NekoErrorKind =
+  | 'invalid_input'
+  | 'not_found'
+  | 'permission_denied'
+  | 'io'
+  | 'window_unavailable'
+  | 'internal';
+
+export interface NekoError {
+  kind: NekoErrorKind;
+  message: string;
+  path?: string;
+  taskId?: string;
+}
+
+const KIND_MESSAGES: Record<NekoErrorKind, string> = {
+  invalid_input: '输入无效',
+  not_found: '找不到对应的任务或文件',
+  permission_denied: '没有访问权限',
+  io: '读写文件失败',
+  window_unavailable: '窗口不可用',
+  internal: '内部错误',
+};
+
+export function isNekoError(error: unknown): error is NekoError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as NekoError).kind === 'string' &&
+    typeof (error as NekoError).message === 'string'
+  );
+}
+
+// 把任意错误转换成给用户看的文字；英文细节只写进控制台日志
+export function errorMessage(error: unknown): string {
+  if (isNekoError(error)) {
+    const text = KIND_MESSAGES[error.kind] ?? KIND_MESSAGES.internal;
+    return error.path ? `${text}（${error.path}）` : text;
+  }
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
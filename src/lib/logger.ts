@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+
+type Level = 'error' | 'warn' | 'info' | 'debug';
+
+function format(args: unknown[]): string {
+  return args
+    .map((arg) => {
+      if (typeof arg === 'string') return arg;
+      if (arg instanceof Error) return arg.stack ?? `${arg.name}: ${arg.message}`;
+      try {
+        return JSON.stringify(arg);
+      } catch {
+        return String(arg);
+      }
+    })
+    .join(' ');
+}
+
+// 把一行日志写进 Rust 端的日志文件；失败时不能再走 console，否则会循环
+export function log(level: Level, ...args: unknown[]) {
+  invoke('log_frontend', { level, message: format(args) }).catch(() => {});
+}
+
+// 发布版本里看不到 devtools，所以 console 的 warn/error 和未捕获的异常也转发到日志文件
+export function installConsoleForwarding() {
+  for (const level of ['error', 'warn', 'info'] as const) {
+    const original = console[level].bind(console);
+    console[level] = (...args: unknown[]) => {
+      original(...args);
+      log(level, ...args);
+    };
+  }
+  window.addEventListener('error', (event) => {
+    log('error', 'Uncaught:', event.error ?? event.message);
+  });
+  window.addEventListener('unhandledrejection', (event) => {
+    log('error', 'Unhandled rejection:', event.reason);
+  });
+}
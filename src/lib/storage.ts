@@ -1,39 +1,33 @@
-import { readTextFile, writeTextFile, mkdir, readDir, exists, remove } from '@tauri-apps/plugin-fs';
-import { desktopDir } from '@tauri-apps/api/path';
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { errorMessage } from './errors';
 
-// TODO: Replace with dynamic path in production
-// For now, using desktop path for testing
-// In production, should use: await desktopDir() + '\\NekoTick'
-const BASE_PATH = 'C:\\Users\\vladelaina\\Desktop\\NekoTick';
-
-export const PATHS = {
-  base: BASE_PATH,
-  tasks: `${BASE_PATH}\\tasks`,
-  progress: `${BASE_PATH}\\progress`,
-  timeTracker: `${BASE_PATH}\\time-tracker`,
-};
-
-// Helper function for future migration to dynamic paths
-export async function initializeBasePath(): Promise<string> {
-  const desktop = await desktopDir();
-  return `${desktop}NekoTick`;
+// 数据目录由 Rust 端解析（便携目录 > --data-dir / NEKOTICK_HOME > XDG 数据目录）
+export interface DataPaths {
+  root: string;
+  tasks: string;
+  progress: string;
+  timeTracker: string;
+  source: 'portable' | 'explicit' | 'xdg';
 }
 
-// 确保目录存在
-export async function ensureDirectories() {
-  try {
-    for (const path of Object.values(PATHS)) {
-      if (!(await exists(path))) {
-        await mkdir(path, { recursive: true });
-      }
-    }
-  } catch (error) {
-    console.error('Failed to create directories:', error);
+let pathsPromise: Promise<DataPaths> | null = null;
+
+export function getPaths(): Promise<DataPaths> {
+  if (!pathsPromise) {
+    pathsPromise = invoke<DataPaths>('get_data_paths');
   }
+  return pathsPromise;
 }
 
 // ============ 待办任务相关 ============
 
+// 一段计时，毫秒时间戳
+export interface TimeInterval {
+  start: number;
+  end: number;
+}
+
 export interface TaskData {
   id: string;
   content: string;
@@ -45,6 +39,16 @@ export interface TaskData {
   parentId: string | null;   // Parent task ID for hierarchical structure
   collapsed: boolean;        // Whether children are hidden
   priority?: 'red' | 'yellow' | 'purple' | 'green' | 'default';  // Task priority
+  estimatedMinutes?: number;      // 预估用时（分钟）
+  actualMinutes?: number;         // 手动记录的用时（分钟），不含 intervals
+  intervals?: TimeInterval[];     // 计时记录
+  extra?: [string, string][];  // 前端不认识的元数据（如旧版的 est/act），保存时原样写回
+}
+
+// 文件里不是任务的行（笔记、空行），after 是它前面那条任务的 id
+export interface NoteLine {
+  after?: string;
+  text: string;
 }
 
 export interface GroupData {
@@ -54,170 +58,17 @@ export interface GroupData {
   tasks: TaskData[];
   createdAt: number;
   updatedAt: number;
-}
-
-// 解析任务 MD 文件
-function parseTasksMd(content: string, _groupId: string): { name: string; pinned: boolean; tasks: TaskData[]; createdAt: number; updatedAt: number } {
-  const lines = content.split('\n');
-  let name = '未命名';
-  let pinned = false;
-  let createdAt = Date.now();
-  let updatedAt = Date.now();
-  const tasks: TaskData[] = [];
-  
-  for (let i = 0; i < lines.length; i++) {
-    const line = lines[i].trim();
-    
-    // 解析标题
-    if (line.startsWith('# ')) {
-      name = line.slice(2).trim();
-      continue;
-    }
-    
-    // 解析元数据
-    if (line.startsWith('pinned:')) {
-      pinned = line.includes('true');
-      continue;
-    }
-    if (line.startsWith('created:')) {
-      createdAt = parseInt(line.split(':')[1].trim()) || Date.now();
-      continue;
-    }
-    if (line.startsWith('updated:')) {
-      updatedAt = parseInt(line.split(':')[1].trim()) || Date.now();
-      continue;
-    }
-    
-    // 解析任务（支持缩进层级）
-    const taskMatch = line.match(/^(\s*)- \[([ x])\] (.+)$/);
-    if (taskMatch) {
-      // const indent = taskMatch[1]; // TODO: 未来可用于从缩进推断层级关系
-      const completed = taskMatch[2] === 'x';
-      const taskContent = taskMatch[3];
-      
-      // 尝试解析任务元数据
-      const metaMatch = taskContent.match(/^(.+?)\s*<!--(.+)-->$/);
-      let id = `task-${Date.now()}-${Math.random().toString(36).slice(2, 7)}`;
-      let taskCreatedAt = Date.now();
-      let order = tasks.length;
-      let content = taskContent;
-      let scheduledTime: string | undefined;
-      let completedAt: number | undefined;
-      let parentId: string | null = null;
-      let collapsed = false;
-      let priority: 'red' | 'yellow' | 'purple' | 'green' | 'default' | undefined;
-      
-      if (metaMatch) {
-        content = metaMatch[1].trim();
-        const meta = metaMatch[2];
-        const idMatch = meta.match(/id:([^,]+)/);
-        const createdMatch = meta.match(/created:([^,]+)/);
-        const orderMatch = meta.match(/order:([^,]+)/);
-        const timeMatch = meta.match(/time:([^,]+)/);
-        const completedAtMatch = meta.match(/completedAt:([^,]+)/);
-        const parentMatch = meta.match(/parent:([^,]+)/);
-        const collapsedMatch = meta.match(/collapsed:true/);
-        const priorityMatch = meta.match(/priority:([^,]+)/);
-        
-        if (idMatch) id = idMatch[1];
-        if (createdMatch) taskCreatedAt = parseInt(createdMatch[1]) || Date.now();
-        if (orderMatch) order = parseInt(orderMatch[1]) || tasks.length;
-        if (timeMatch) scheduledTime = timeMatch[1];
-        if (completedAtMatch) completedAt = parseInt(completedAtMatch[1]);
-        if (parentMatch) parentId = parentMatch[1];
-        if (collapsedMatch) collapsed = true;
-        if (priorityMatch) priority = priorityMatch[1] as any;
-      }
-      
-      tasks.push({
-        id,
-        content,
-        completed,
-        createdAt: taskCreatedAt,
-        completedAt,
-        scheduledTime,
-        order,
-        parentId,
-        collapsed,
-        priority,
-      });
-    }
-  }
-  
-  return { name, pinned, tasks, createdAt, updatedAt };
-}
-
-// 生成任务 MD 文件 (支持层级结构)
-function generateTasksMd(group: GroupData): string {
-  const lines: string[] = [];
-  
-  lines.push(`# ${group.name}`);
-  lines.push('');
-  lines.push(`pinned: ${group.pinned}`);
-  lines.push(`created: ${group.createdAt}`);
-  lines.push(`updated: ${group.updatedAt}`);
-  lines.push('');
-  
-  // 递归生成任务（带缩进）
-  const renderTask = (task: any, indent: string = '') => {
-    const checkbox = task.completed ? '[x]' : '[ ]';
-    let meta = `id:${task.id},created:${task.createdAt},order:${task.order}`;
-    if (task.scheduledTime) meta += `,time:${task.scheduledTime}`;
-    if (task.completedAt) meta += `,completedAt:${task.completedAt}`;
-    if (task.parentId) meta += `,parent:${task.parentId}`;
-    if (task.collapsed) meta += `,collapsed:true`;
-    if (task.priority && task.priority !== 'default') meta += `,priority:${task.priority}`;
-    
-    lines.push(`${indent}- ${checkbox} ${task.content} <!--${meta}-->`);
-
-    
-    // 递归渲染子任务
-    const children = group.tasks
-      .filter((t) => t.parentId === task.id)
-      .sort((a, b) => a.order - b.order);
-    
-    for (const child of children) {
-      renderTask(child, indent + '  ');
-    }
-  };
-  
-  // 只渲染顶层任务（没有 parentId 的）
-  const topLevelTasks = group.tasks
-    .filter((t) => !t.parentId)
-    .sort((a, b) => a.order - b.order);
-  
-  for (const task of topLevelTasks) {
-    renderTask(task);
-  }
-  
-  return lines.join('\n');
+  // 以下由后端解析，前端原样带回，保存时文件才能逐字节不变
+  extra?: NoteLine[];
+  lineEnding?: 'lf' | 'crlf';
+  finalNewline?: boolean;
 }
 
 // 读取所有分组
 export async function loadGroups(): Promise<GroupData[]> {
   try {
-    await ensureDirectories();
-    
-    const entries = await readDir(PATHS.tasks);
-    const groups: GroupData[] = [];
-    
-    for (const entry of entries) {
-      if (entry.name?.endsWith('.md')) {
-        const filePath = `${PATHS.tasks}\\${entry.name}`;
-        const content = await readTextFile(filePath);
-        const groupId = entry.name.replace('.md', '');
-        const parsed = parseTasksMd(content, groupId);
-        
-        groups.push({
-          id: groupId,
-          name: parsed.name,
-          pinned: parsed.pinned,
-          tasks: parsed.tasks,
-          createdAt: parsed.createdAt,
-          updatedAt: parsed.updatedAt,
-        });
-      }
-    }
+    // Rust 端读取并解析，文件损坏时自动回退到最近的 .bak
+    const groups = await invoke<GroupData[]>('load_groups');
     
     // 如果没有分组，创建默认分组
     if (groups.length === 0) {
@@ -235,50 +86,274 @@ export async function loadGroups(): Promise<GroupData[]> {
     
     return groups;
   } catch (error) {
+    // 读取失败时不能写入默认分组，否则会覆盖读不出来的真实文件
     console.error('Failed to load groups:', error);
-    // Return default group on error
-    const defaultGroup: GroupData = {
-      id: 'default',
-      name: '收集箱',
-      pinned: false,
-      tasks: [],
-      createdAt: Date.now(),
-      updatedAt: Date.now(),
-    };
-    try {
-      await saveGroup(defaultGroup);
-    } catch (saveError) {
-      console.error('Failed to create default group:', saveError);
-    }
-    return [defaultGroup];
+    throw new Error('读取任务失败：' + errorMessage(error));
   }
 }
 
-// 保存分组
-export async function saveGroup(group: GroupData): Promise<void> {
+// 保存分组（原子写入：临时文件 + fsync + rename，并保留 .bak 备份）
+// 如果文件在磁盘上被外部修改过，Rust 端会做三方合并并返回合并后的分组
+export async function saveGroup(group: GroupData): Promise<GroupData | null> {
   try {
-    await ensureDirectories();
-    const filePath = `${PATHS.tasks}\\${group.id}.md`;
-    const content = generateTasksMd(group);
-    await writeTextFile(filePath, content);
+    return await invoke<GroupData | null>('save_group', { group });
   } catch (error) {
     console.error('Failed to save group:', error);
-    throw new Error('保存失败：' + (error instanceof Error ? error.message : '未知错误'));
+    throw new Error('保存失败：' + errorMessage(error));
+  }
+}
+
+// ============ 数据检查（重复 ID、丢失的父任务、循环、顺序） ============
+
+export type VaultIssue =
+  | { kind: 'duplicateId'; groupId: string; taskId: string; newId: string }
+  | { kind: 'danglingParent'; groupId: string; taskId: string; parentId: string }
+  | { kind: 'parentCycle'; groupId: string; taskIds: string[]; cut: string }
+  | { kind: 'orderOutOfRange'; groupId: string; parentId: string | null; orders: number[] };
+
+export function describeIssue(issue: VaultIssue): string {
+  switch (issue.kind) {
+    case 'duplicateId':
+      return `重复的任务 ID ${issue.taskId}，将改为 ${issue.newId}`;
+    case 'danglingParent':
+      return `任务 ${issue.taskId} 的父任务 ${issue.parentId} 不存在，将移到顶层`;
+    case 'parentCycle':
+      return `任务 ${issue.taskIds.join(' → ')} 互为父任务，将把 ${issue.cut} 移到顶层`;
+    case 'orderOutOfRange':
+      return `${issue.parentId ? `任务 ${issue.parentId} 的子任务` : '顶层任务'}顺序异常，将重新编号`;
+  }
+}
+
+// 只检查不写入
+export async function checkVault(): Promise<VaultIssue[]> {
+  return invoke<VaultIssue[]>('check_vault');
+}
+
+// 按检查结果修复并一次性写入，返回被修改的分组
+export async function repairVault(): Promise<{ issues: VaultIssue[]; groups: GroupData[] }> {
+  return invoke('repair_vault');
+}
+
+// 把日志、版本信息和（脱敏后的）数据目录结构打包成 zip，返回文件路径
+export async function collectDiagnostics(): Promise<string> {
+  return invoke<string>('collect_diagnostics');
+}
+
+export interface AuditTotals {
+  tasks: number;
+  completed: number;
+  estimatedMinutes: number;
+  actualMinutes: number;
+}
+
+export interface AuditSummary {
+  total: AuditTotals;
+  groups: { groupId: string; name: string; totals: AuditTotals }[];
+  tags: { tag: string; totals: AuditTotals }[];
+}
+
+// 预估与实际用时汇总（按分组和 #标签）
+export async function getTimeAudit(): Promise<AuditSummary> {
+  return invoke<AuditSummary>('time_audit');
+}
+
+export interface Migration {
+  group: GroupData | null;
+  imported: number;
+  skipped: number;
+}
+
+// 把旧版 Documents/NekoTick/nekotick.md 导入为新分组；已存在的任务会跳过，重复执行不会重复导入
+export async function migrateLegacy(): Promise<Migration> {
+  return invoke<Migration>('migrate_legacy');
+}
+
+// 批量移动任务（连同子任务）到另一个分组，所有受影响的文件一次性写入
+// 返回所有发生变化的分组
+export async function moveTasks(
+  taskIds: string[],
+  targetGroupId: string,
+  index?: number
+): Promise<GroupData[]> {
+  try {
+    return await invoke<GroupData[]>('move_tasks', { taskIds, targetGroupId, index });
+  } catch (error) {
+    console.error('Failed to move tasks:', error);
+    throw new Error('移动失败：' + errorMessage(error));
   }
 }
 
-// 删除分组
+// 拖拽结束时调用：如果任务被拖到窗口外，Rust 端把它（连同子任务）导出为 .md 清单
+// 返回导出的文件路径，普通拖放返回 null
+export async function exportDrag(taskIds: string[]): Promise<string | null> {
+  return invoke<string | null>('export_drag', { taskIds });
+}
+
+// 把拖进窗口的 .md/.txt 文件中的复选框行导入到分组，返回更新后的分组
+export async function importFiles(groupId: string, files: string[]): Promise<GroupData> {
+  return invoke<GroupData>('import_files', { groupId, files });
+}
+
+// 后端设置（保存在数据目录的 settings.json）
+export interface Settings {
+  exportOnDrop: boolean;
+  outbox?: string | null;
+  // 纯净 Markdown：任务行不带注释，元数据存放在 .nekotick/index.json
+  cleanMarkdown: boolean;
+  pomodoro: PomodoroConfig;
+  idle: IdleConfig;
+  // 记录前台应用的使用时间到 time-tracker/time-log.md（目前只支持 Linux X11）
+  trackApps: boolean;
+  // 在本机开放端口接收浏览器扩展的心跳，记录网站访问时间
+  trackWebsites: boolean;
+}
+
+// 浏览器扩展连接本机的端口和令牌，也写在 .nekotick/extension.json
+export interface WebEndpoint {
+  port: number;
+  token: string;
+}
+
+export async function getSettings(): Promise<Settings> {
+  return invoke<Settings>('get_settings');
+}
+
+export async function saveSettings(settings: Settings): Promise<void> {
+  await invoke('save_settings', { settings });
+}
+
+// 删除分组（.bak 备份会保留）
 export async function deleteGroup(groupId: string): Promise<void> {
   try {
-    const filePath = `${PATHS.tasks}\\${groupId}.md`;
-    if (await exists(filePath)) {
-      await remove(filePath);
-    }
+    await invoke('delete_group', { groupId });
   } catch (error) {
     console.error('Failed to delete group:', error);
+    throw new Error('删除分组失败：' + errorMessage(error));
+  }
+}
+
+// ============ 外部修改（Rust 端监听 tasks 目录） ============
+
+// 采用了外部修改后的版本，之后的保存以它为合并基准
+export async function adoptGroup(group: GroupData): Promise<void> {
+  try {
+    await invoke('adopt_group', { group });
+  } catch (error) {
+    console.error('Failed to adopt group:', error);
   }
 }
 
+export function onGroupChanged(handler: (group: GroupData) => void): Promise<UnlistenFn> {
+  return listen<{ group: GroupData }>('group-changed', (event) => handler(event.payload.group));
+}
+
+export function onGroupRemoved(handler: (id: string) => void): Promise<UnlistenFn> {
+  return listen<{ id: string }>('group-removed', (event) => handler(event.payload.id));
+}
+
+// ============ 任务计时（Rust 端计时，窗口隐藏或刷新也不会中断） ============
+
+export interface RunningTimer {
+  taskId: string;
+  startedAt: number;
+}
+
+// 番茄钟时长（分钟），每 longBreakEvery 个番茄后是一次长休息
+export interface PomodoroConfig {
+  workMinutes: number;
+  shortBreakMinutes: number;
+  longBreakMinutes: number;
+  longBreakEvery: number;
+}
+
+export type PomodoroPhase = 'work' | 'shortBreak' | 'longBreak';
+
+export interface PomodoroSession {
+  phase: PomodoroPhase;
+  taskId?: string | null;
+  startedAt: number;
+  endsAt: number;
+}
+
+export interface PomodoroState {
+  config: PomodoroConfig;
+  session?: PomodoroSession | null;
+  streak: number;
+}
+
+// 空闲 minutes 分钟后暂停计时（0 表示不检测）；autoPause 时回来直接继续并丢弃空闲时间，否则询问
+export interface IdleConfig {
+  minutes: number;
+  autoPause: boolean;
+}
+
+// 因空闲暂停的计时，用户回来后 returnedAt 有值，等待 resolveIdle
+export interface IdleBreak {
+  since: number;
+  returnedAt?: number | null;
+  tasks: string[];
+  pomodoro?: PomodoroSession | null;  // 暂停的番茄，处理空闲时间后继续
+}
+
+export type IdleChoice =
+  | { action: 'keep' }
+  | { action: 'discard' }
+  | { action: 'reassign'; taskId: string };
+
+export interface TimerStatus {
+  running: RunningTimer[];
+  pomodoro: PomodoroState;
+  idle?: IdleBreak | null;
+  now: number;  // 后端时钟
+}
+
+export interface TimerUpdate {
+  status: TimerStatus;
+  groups: GroupData[];  // 计时记录发生变化的分组
+}
+
+export async function getTimerStatus(): Promise<TimerStatus> {
+  return invoke<TimerStatus>('timer_status');
+}
+
+// 默认只允许一个计时器：开始新的会先停止其它计时
+export async function startTimer(taskId: string, concurrent = false): Promise<TimerUpdate> {
+  return invoke<TimerUpdate>('timer_start', { taskId, concurrent });
+}
+
+export async function stopTimer(taskId: string): Promise<TimerUpdate> {
+  return invoke<TimerUpdate>('timer_stop', { taskId });
+}
+
+export function onTimerTick(handler: (status: TimerStatus) => void): Promise<UnlistenFn> {
+  return listen<TimerStatus>('timer-tick', (event) => handler(event.payload));
+}
+
+// 番茄钟的工作时段会启动任务计时，完成的番茄按天计入 time-log.md
+export async function startPomodoro(taskId?: string): Promise<TimerUpdate> {
+  return invoke<TimerUpdate>('pomodoro_start', { taskId: taskId ?? null });
+}
+
+// 放弃当前番茄或休息，已工作的时间仍记在任务上
+export async function stopPomodoro(): Promise<TimerUpdate> {
+  return invoke<TimerUpdate>('pomodoro_stop');
+}
+
+// 处理空闲时间（保留 / 丢弃 / 记到其它任务），然后从回来的时刻继续计时
+export async function resolveIdle(choice: IdleChoice): Promise<TimerUpdate> {
+  return invoke<TimerUpdate>('timer_resolve_idle', { choice });
+}
+
+// 未开启网站记录时为 null
+export async function getWebEndpoint(): Promise<WebEndpoint | null> {
+  return invoke<WebEndpoint | null>('web_endpoint');
+}
+
+// 后端自己推进了计时（番茄到点），附带记录变化的分组
+export function onTimerUpdate(handler: (update: TimerUpdate) => void): Promise<UnlistenFn> {
+  return listen<TimerUpdate>('timer-update', (event) => handler(event.payload));
+}
+
 // ============ 进度相关 ============
 
 export interface ProgressData {
@@ -299,108 +374,23 @@ export interface ProgressData {
   createdAt: number;
 }
 
-// 解析进度 MD 文件
-function parseProgressMd(content: string): ProgressData[] {
-  const items: ProgressData[] = [];
-  const blocks = content.split('\n## ').slice(1); // 跳过第一个块（标题）
-  
-  for (const block of blocks) {
-    const lines = block.split('\n');
-    const item: Partial<ProgressData> = {};
-    
-    // 第一行是标题
-    if (lines.length > 0 && lines[0].trim()) {
-      item.title = lines[0].trim();
-    }
-    
-    for (const line of lines.slice(1)) {
-      const trimmed = line.trim();
-      if (trimmed.startsWith('- id:')) {
-        item.id = trimmed.slice(5).trim();
-      } else if (trimmed.startsWith('- type:')) {
-        item.type = trimmed.slice(7).trim() as 'progress' | 'counter';
-      } else if (trimmed.startsWith('- note:')) {
-        item.note = trimmed.slice(7).trim() || undefined;
-      } else if (trimmed.startsWith('- direction:')) {
-        item.direction = trimmed.slice(12).trim() as 'increment' | 'decrement';
-      } else if (trimmed.startsWith('- total:')) {
-        item.total = parseInt(trimmed.slice(8).trim()) || 0;
-      } else if (trimmed.startsWith('- step:')) {
-        item.step = parseInt(trimmed.slice(7).trim()) || 1;
-      } else if (trimmed.startsWith('- unit:')) {
-        item.unit = trimmed.slice(7).trim();
-      } else if (trimmed.startsWith('- current:')) {
-        item.current = parseInt(trimmed.slice(10).trim()) || 0;
-      } else if (trimmed.startsWith('- todayCount:')) {
-        item.todayCount = parseInt(trimmed.slice(13).trim()) || 0;
-      } else if (trimmed.startsWith('- lastUpdateDate:')) {
-        item.lastUpdateDate = trimmed.slice(17).trim() || undefined;
-      } else if (trimmed.startsWith('- frequency:')) {
-        item.frequency = trimmed.slice(12).trim() as 'daily' | 'weekly' | 'monthly';
-      } else if (trimmed.startsWith('- createdAt:')) {
-        item.createdAt = parseInt(trimmed.slice(12).trim()) || Date.now();
-      }
-    }
-    
-    if (item.id && item.type && item.title) {
-      items.push(item as ProgressData);
-    }
-  }
-  
-  return items;
-}
-
-// 生成进度 MD 文件
-function generateProgressMd(items: ProgressData[]): string {
-  const lines: string[] = ['# 进度列表', ''];
-  
-  for (const item of items) {
-    lines.push(`## ${item.title}`);
-    lines.push(`- id: ${item.id}`);
-    lines.push(`- type: ${item.type}`);
-    if (item.note) lines.push(`- note: ${item.note}`);
-    if (item.direction) lines.push(`- direction: ${item.direction}`);
-    if (item.total !== undefined) lines.push(`- total: ${item.total}`);
-    lines.push(`- step: ${item.step}`);
-    lines.push(`- unit: ${item.unit}`);
-    lines.push(`- current: ${item.current}`);
-    lines.push(`- todayCount: ${item.todayCount}`);
-    if (item.lastUpdateDate) lines.push(`- lastUpdateDate: ${item.lastUpdateDate}`);
-    if (item.frequency) lines.push(`- frequency: ${item.frequency}`);
-    lines.push(`- createdAt: ${item.createdAt}`);
-    lines.push('');
-  }
-  
-  return lines.join('\n');
-}
-
 // 读取所有进度
 export async function loadProgress(): Promise<ProgressData[]> {
   try {
-    await ensureDirectories();
-    const filePath = `${PATHS.progress}\\progress.md`;
-    
-    if (await exists(filePath)) {
-      const content = await readTextFile(filePath);
-      return parseProgressMd(content);
-    }
-    
-    return [];
+    return await invoke<ProgressData[]>('load_progress');
   } catch (error) {
     console.error('Failed to load progress:', error);
-    return [];
+    throw new Error('读取进度失败：' + errorMessage(error));
   }
 }
 
 // 保存进度
 export async function saveProgress(items: ProgressData[]): Promise<void> {
   try {
-    await ensureDirectories();
-    const filePath = `${PATHS.progress}\\progress.md`;
-    const content = generateProgressMd(items);
-    await writeTextFile(filePath, content);
+    await invoke('save_progress', { items });
   } catch (error) {
     console.error('Failed to save progress:', error);
+    throw new Error('保存进度失败：' + errorMessage(error));
   }
 }
 
@@ -415,71 +405,13 @@ export interface DayTimeData {
   date: string;
   apps: AppUsageData[];
   websites: AppUsageData[];
-}
-
-// 解析时间追踪 MD 文件
-function parseTimeTrackerMd(content: string): DayTimeData[] {
-  const days: DayTimeData[] = [];
-  const blocks = content.split('\n## ').filter(Boolean);
-  
-  for (const block of blocks) {
-    const lines = block.startsWith('## ') ? block.slice(3).split('\n') : block.split('\n');
-    let currentDate = '';
-    let currentSection: 'apps' | 'websites' | null = null;
-    const apps: AppUsageData[] = [];
-    const websites: AppUsageData[] = [];
-    
-    for (const line of lines) {
-      const trimmed = line.trim();
-      
-      // 解析日期 (格式: 2024-11-30 或标题行)
-      if (trimmed.match(/^\d{4}-\d{2}-\d{2}$/)) {
-        currentDate = trimmed;
-        continue;
-      }
-      
-      // 解析section
-      if (trimmed.includes('应用使用时间')) {
-        currentSection = 'apps';
-        continue;
-      }
-      if (trimmed.includes('网站访问时间')) {
-        currentSection = 'websites';
-        continue;
-      }
-      
-      // 解析数据行 (格式: - AppName: 1234秒)
-      const dataMatch = trimmed.match(/^- (.+?):\s*(\d+)秒?$/);
-      if (dataMatch && currentSection) {
-        const item = { name: dataMatch[1].trim(), duration: parseInt(dataMatch[2]) };
-        if (currentSection === 'apps') {
-          apps.push(item);
-        } else {
-          websites.push(item);
-        }
-      }
-    }
-    
-    if (currentDate && (apps.length > 0 || websites.length > 0)) {
-      days.push({ date: currentDate, apps, websites });
-    }
-  }
-  
-  return days;
+  pomodoros: number;  // 当天完成的番茄数
 }
 
 // 读取时间追踪数据
 export async function loadTimeTracker(): Promise<DayTimeData[]> {
   try {
-    await ensureDirectories();
-    const filePath = `${PATHS.timeTracker}\\time-log.md`;
-    
-    if (await exists(filePath)) {
-      const content = await readTextFile(filePath);
-      return parseTimeTrackerMd(content);
-    }
-    
-    return [];
+    return await invoke<DayTimeData[]>('load_time_log');
   } catch (error) {
     console.error('Failed to load time tracker:', error);
     return [];
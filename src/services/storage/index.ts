@@ -1 +0,0 @@
-export { LocalStorageRepository, localStorageRepo } from './local';
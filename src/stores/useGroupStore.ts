@@ -1,6 +1,18 @@
 import { create } from 'zustand';
 import { nanoid } from 'nanoid';
-import { loadGroups, saveGroup, deleteGroup as deleteGroupFile, type GroupData } from '@/lib/storage';
+import {
+  loadGroups,
+  saveGroup,
+  moveTasks,
+  deleteGroup as deleteGroupFile,
+  checkVault,
+  repairVault,
+  describeIssue,
+  type GroupData,
+  type NoteLine,
+  type TimeInterval,
+} from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
 import { useToastStore } from './useToastStore';
 
 // Note: This store uses 'StoreTask' with 'completed' field for persistence.
@@ -12,6 +24,10 @@ export interface Group {
   color?: string;
   createdAt: number;
   pinned?: boolean;
+  // Kept from the file and passed back on save
+  extra?: NoteLine[];
+  lineEnding?: 'lf' | 'crlf';
+  finalNewline?: boolean;
 }
 
 // Priority levels: red (highest) > yellow > purple > green > default (lowest)
@@ -32,6 +48,10 @@ export interface StoreTask {
   // Hierarchical structure (nested tasks)
   parentId: string | null;  // Parent task ID, null for top-level
   collapsed: boolean;       // Whether children are hidden
+  estimatedMinutes?: number;
+  actualMinutes?: number;      // Logged by hand, on top of intervals
+  intervals?: TimeInterval[];
+  extra?: [string, string][];  // Metadata the UI doesn't use, kept for saving
 }
 
 interface GroupStore {
@@ -46,6 +66,10 @@ interface GroupStore {
   // Drag state for cross-group drag
   draggingTaskId: string | null;
   setDraggingTaskId: (id: string | null) => void;
+  // Ctrl/Cmd+click selection, dragged together
+  selectedTaskIds: string[];
+  toggleTaskSelection: (id: string) => void;
+  clearTaskSelection: () => void;
   setHideCompleted: (hide: boolean) => void;
   setSearchQuery: (query: string) => void;
   
@@ -70,6 +94,86 @@ interface GroupStore {
   reorderTasks: (activeId: string, overId: string) => void;
   crossStatusReorder: (activeId: string, overId: string) => void;
   moveTaskToGroup: (taskId: string, targetGroupId: string, overTaskId?: string | null) => void;
+  moveTasksToGroup: (taskIds: string[], targetGroupId: string, overTaskId?: string | null) => Promise<void>;
+  
+  // External edits pushed by the Rust file watcher
+  applyExternalGroup: (data: GroupData) => void;
+  removeExternalGroup: (id: string) => void;
+}
+
+// 文件数据 -> store 数据（重复的任务 ID 只显示第一次出现的；修复见 reviewVault）
+function fromGroupData(gd: GroupData): { group: Group; tasks: StoreTask[] } {
+  const tasks: StoreTask[] = [];
+  const seenIds = new Set<string>();
+  for (const td of gd.tasks) {
+    if (seenIds.has(td.id)) {
+      console.warn(`Hiding duplicate task id ${td.id} in group ${gd.id}`);
+      continue;
+    }
+    seenIds.add(td.id);
+    
+    tasks.push({
+      id: td.id,
+      content: td.content,
+      completed: td.completed,
+      createdAt: td.createdAt,
+      completedAt: td.completedAt,
+      scheduledTime: td.scheduledTime,
+      order: td.order,
+      groupId: gd.id,
+      parentId: td.parentId,
+      collapsed: td.collapsed,
+      priority: td.priority,
+      estimatedMinutes: td.estimatedMinutes,
+      actualMinutes: td.actualMinutes,
+      intervals: td.intervals,
+      extra: td.extra,
+    });
+  }
+  
+  return {
+    group: {
+      id: gd.id,
+      name: gd.name,
+      pinned: gd.pinned,
+      createdAt: gd.createdAt,
+      extra: gd.extra,
+      lineEnding: gd.lineEnding,
+      finalNewline: gd.finalNewline,
+    },
+    tasks,
+  };
+}
+
+// 在任何写入之前检查数据问题；用户同意后由 Rust 端修复，否则重复的任务会在下次保存时丢失
+async function reviewVault(allGroups: GroupData[]): Promise<GroupData[]> {
+  let issues;
+  try {
+    issues = await checkVault();
+  } catch (error) {
+    console.error('Failed to check vault:', error);
+    return allGroups;
+  }
+  if (issues.length === 0) return allGroups;
+
+  console.warn('Vault check found issues:', issues);
+  const shown = issues.slice(0, 8).map((i) => `• ${describeIssue(i)}`);
+  if (issues.length > shown.length) shown.push(`…还有 ${issues.length - shown.length} 个`);
+  const ok = window.confirm(
+    `数据检查发现 ${issues.length} 个问题：\n\n${shown.join('\n')}\n\n现在修复吗？原文件会保留为 .bak 备份。`
+  );
+  if (!ok) return allGroups;
+
+  try {
+    const { groups } = await repairVault();
+    const repaired = new Map(groups.map((g) => [g.id, g]));
+    useToastStore.getState().addToast(`已修复 ${issues.length} 个问题`, 'success');
+    return allGroups.map((g) => repaired.get(g.id) ?? g);
+  } catch (error) {
+    console.error('Failed to repair vault:', error);
+    useToastStore.getState().addToast('修复失败，数据未改动', 'error', 4000);
+    return allGroups;
+  }
 }
 
 // 保存分组到文件
@@ -106,19 +210,26 @@ async function persistGroup(groups: Group[], tasks: StoreTask[], groupId: string
         order: t.order,
         parentId: t.parentId,
         collapsed: t.collapsed,
+        priority: t.priority,
+        estimatedMinutes: t.estimatedMinutes,
+        actualMinutes: t.actualMinutes,
+        intervals: t.intervals,
+        extra: t.extra,
       })),
       createdAt: group.createdAt,
       updatedAt: Date.now(),
+      extra: group.extra,
+      lineEnding: group.lineEnding,
+      finalNewline: group.finalNewline,
     };
     
     try {
-      await saveGroup(groupData);
+      const merged = await saveGroup(groupData);
+      if (merged) {
+        useGroupStore.getState().applyExternalGroup(merged);
+      }
     } catch (error) {
-      useToastStore.getState().addToast(
-        error instanceof Error ? error.message : '保存任务失败', 
-        'error', 
-        4000
-      );
+      useToastStore.getState().addToast(errorMessage(error), 'error', 4000);
     }
     return;
   }
@@ -138,23 +249,29 @@ async function persistGroup(groups: Group[], tasks: StoreTask[], groupId: string
       parentId: t.parentId,
       collapsed: t.collapsed,
       priority: t.priority,
+      estimatedMinutes: t.estimatedMinutes,
+      actualMinutes: t.actualMinutes,
+      intervals: t.intervals,
+      extra: t.extra,
     })),
     createdAt: group.createdAt,
     updatedAt: Date.now(),
+    extra: group.extra,
+    lineEnding: group.lineEnding,
+    finalNewline: group.finalNewline,
   };
   
   try {
-    await saveGroup(groupData);
+    const merged = await saveGroup(groupData);
+    if (merged) {
+      useGroupStore.getState().applyExternalGroup(merged);
+    }
   } catch (error) {
-    useToastStore.getState().addToast(
-      error instanceof Error ? error.message : '保存任务失败', 
-      'error', 
-      4000
-    );
+    useToastStore.getState().addToast(errorMessage(error), 'error', 4000);
   }
 }
 
-export const useGroupStore = create<GroupStore>()((set, _get) => ({
+export const useGroupStore = create<GroupStore>()((set, get) => ({
   groups: [],
   tasks: [],
   activeGroupId: 'default',
@@ -165,48 +282,36 @@ export const useGroupStore = create<GroupStore>()((set, _get) => ({
   
   draggingTaskId: null,
   setDraggingTaskId: (id) => set({ draggingTaskId: id }),
+  selectedTaskIds: [],
+  toggleTaskSelection: (id) => set((state) => ({
+    selectedTaskIds: state.selectedTaskIds.includes(id)
+      ? state.selectedTaskIds.filter(s => s !== id)
+      : [...state.selectedTaskIds, id],
+  })),
+  clearTaskSelection: () => set({ selectedTaskIds: [] }),
   setHideCompleted: (hide) => set({ hideCompleted: hide }),
   setSearchQuery: (query) => set({ searchQuery: query }),
 
   loadData: async () => {
-    const allGroups = await loadGroups();
+    let loadedGroups: GroupData[];
+    try {
+      loadedGroups = await loadGroups();
+    } catch (error) {
+      // 保持未加载状态，避免在读不出来的分组上继续编辑和保存
+      useToastStore.getState().addToast(errorMessage(error), 'error', 6000);
+      return;
+    }
+    const allGroups = await reviewVault(loadedGroups);
     const groups: Group[] = [];
     const tasks: StoreTask[] = [];
     
     for (const gd of allGroups) {
-      
-      groups.push({
-        id: gd.id,
-        name: gd.name,
-        pinned: gd.pinned,
-        createdAt: gd.createdAt,
-      });
-      
-      // Auto-fix: remove duplicate task IDs (keep only first occurrence)
-      const seenIds = new Set<string>();
-      for (const td of gd.tasks) {
-        if (seenIds.has(td.id)) {
-          continue; // Skip duplicate
-        }
-        seenIds.add(td.id);
-        
-        tasks.push({
-          id: td.id,
-          content: td.content,
-          completed: td.completed,
-          createdAt: td.createdAt,
-          completedAt: td.completedAt,
-          scheduledTime: td.scheduledTime,
-          order: td.order,
-          groupId: gd.id,
-          parentId: td.parentId,
-          collapsed: td.collapsed,
-          priority: td.priority,
-        });
-      }
+      const data = fromGroupData(gd);
+      groups.push(data.group);
+      tasks.push(...data.tasks);
     }
     
-    // All duplicates have been removed during loading
+    // Duplicates the user chose not to repair are hidden by fromGroupData
     
     // 按置顶排序
     groups.sort((a, b) => {
@@ -247,7 +352,9 @@ export const useGroupStore = create<GroupStore>()((set, _get) => ({
   
   deleteGroup: (id) => set((state) => {
     if (id === 'default') return state;
-    deleteGroupFile(id);
+    deleteGroupFile(id).catch((error) => {
+      useToastStore.getState().addToast(errorMessage(error), 'error', 4000);
+    });
     return {
       groups: state.groups.filter((g) => g.id !== id),
       tasks: state.tasks.filter((t) => t.groupId !== id),
@@ -651,4 +758,46 @@ export const useGroupStore = create<GroupStore>()((set, _get) => ({
     
     return { tasks: newTasks, activeGroupId: targetGroupId };
   }),
+  
+  // 多选移动：由 Rust 端读写文件，完成后采用返回的分组
+  moveTasksToGroup: async (taskIds, targetGroupId, overTaskId) => {
+    let index: number | undefined;
+    if (overTaskId) {
+      const targetTopLevel = get().tasks
+        .filter(t => t.groupId === targetGroupId && !t.parentId && !taskIds.includes(t.id))
+        .sort((a, b) => a.order - b.order);
+      const overIndex = targetTopLevel.findIndex(t => t.id === overTaskId);
+      if (overIndex !== -1) index = overIndex;
+    }
+    
+    try {
+      const changed = await moveTasks(taskIds, targetGroupId, index);
+      changed.forEach(gd => get().applyExternalGroup(gd));
+      set({ activeGroupId: targetGroupId, selectedTaskIds: [] });
+    } catch (error) {
+      useToastStore.getState().addToast(errorMessage(error), 'error', 4000);
+    }
+  },
+  
+  applyExternalGroup: (gd) => set((state) => {
+    const { group, tasks } = fromGroupData(gd);
+    const exists = state.groups.some(g => g.id === group.id);
+    const groups = exists
+      ? state.groups.map(g => (g.id === group.id ? { ...g, ...group } : g))
+      : [...state.groups, group];
+    
+    return {
+      groups,
+      tasks: [...state.tasks.filter(t => t.groupId !== group.id), ...tasks],
+    };
+  }),
+  
+  removeExternalGroup: (id) => set((state) => {
+    if (!state.groups.some(g => g.id === id)) return state;
+    return {
+      groups: state.groups.filter((g) => g.id !== id),
+      tasks: state.tasks.filter((t) => t.groupId !== id),
+      activeGroupId: state.activeGroupId === id ? 'default' : state.activeGroupId,
+    };
+  }),
 }));
@@ -1,6 +1,8 @@
 import { create } from 'zustand';
 import { nanoid } from 'nanoid';
 import { loadProgress, saveProgress, type ProgressData } from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
+import { useToastStore } from './useToastStore';
 
 export interface ProgressItem {
   id: string;
@@ -119,7 +121,13 @@ function toStorageFormat(item: ProgressOrCounter): ProgressData {
 
 // 保存到文件
 async function persistItems(items: ProgressOrCounter[]) {
-  await saveProgress(items.map(toStorageFormat));
+  // 没读出来的文件不能用空列表覆盖
+  if (!useProgressStore.getState().loaded) return;
+  try {
+    await saveProgress(items.map(toStorageFormat));
+  } catch (error) {
+    useToastStore.getState().addToast(errorMessage(error), 'error', 4000);
+  }
 }
 
 export const useProgressStore = create<ProgressStore>((set, get) => ({
@@ -128,8 +136,12 @@ export const useProgressStore = create<ProgressStore>((set, get) => ({
   
   loadItems: async () => {
     if (get().loaded) return;
-    const data = await loadProgress();
-    set({ items: data.map(fromStorageFormat), loaded: true });
+    try {
+      const data = await loadProgress();
+      set({ items: data.map(fromStorageFormat), loaded: true });
+    } catch (error) {
+      useToastStore.getState().addToast(errorMessage(error), 'error', 4000);
+    }
   },
   
   addProgress: (data) => set((state) => {
@@ -0,0 +1,121 @@
+import { create } from 'zustand';
+import {
+  adoptGroup,
+  getTimerStatus,
+  startTimer,
+  stopTimer,
+  startPomodoro,
+  stopPomodoro,
+  resolveIdle,
+  onTimerTick,
+  onTimerUpdate,
+  type IdleBreak,
+  type IdleChoice,
+  type PomodoroState,
+  type RunningTimer,
+  type TimerStatus,
+  type TimerUpdate,
+} from '@/lib/storage';
+import { errorMessage } from '@/lib/errors';
+import { useGroupStore } from './useGroupStore';
+import { useToastStore } from './useToastStore';
+
+interface TimerStore {
+  running: RunningTimer[];
+  pomodoro: PomodoroState | null;
+  idle: IdleBreak | null;
+  // 后端时钟减去本地时钟，用来在两次 tick 之间显示准确的耗时
+  clockOffset: number;
+  now: number;
+
+  init: () => Promise<() => void>;
+  start: (taskId: string) => Promise<void>;
+  stop: (taskId: string) => Promise<void>;
+  startPomodoro: (taskId?: string) => Promise<void>;
+  stopPomodoro: () => Promise<void>;
+  resolveIdle: (choice: IdleChoice) => Promise<void>;
+}
+
+function fromStatus(status: TimerStatus) {
+  return {
+    running: status.running,
+    pomodoro: status.pomodoro,
+    idle: status.idle ?? null,
+    clockOffset: status.now - Date.now(),
+    now: status.now,
+  };
+}
+
+export const useTimerStore = create<TimerStore>((set, get) => {
+  const apply = (update: TimerUpdate) => {
+    set(fromStatus(update.status));
+    // 停止的计时已经写进任务文件，采用后端返回的分组
+    update.groups.forEach((group) => {
+      useGroupStore.getState().applyExternalGroup(group);
+      adoptGroup(group);
+    });
+  };
+
+  const run = async (action: () => Promise<TimerUpdate>) => {
+    try {
+      apply(await action());
+    } catch (error) {
+      console.error('Timer command failed:', error);
+      useToastStore.getState().addToast(`计时失败：${errorMessage(error)}`, 'error', 4000);
+    }
+  };
+
+  return {
+    running: [],
+    pomodoro: null,
+    idle: null,
+    clockOffset: 0,
+    now: Date.now(),
+
+    // 读取当前状态（重启后会继续之前的计时）并订阅每秒的 tick
+    init: async () => {
+      const unlisten = onTimerTick((status) => set(fromStatus(status)));
+      // 番茄到点由后端推进：工作结束进入休息，休息结束回到空闲
+      const unlistenUpdate = onTimerUpdate((update) => {
+        const before = get().pomodoro?.session?.phase;
+        const after = update.status.pomodoro.session?.phase;
+        apply(update);
+        if (before === 'work' && (after === 'shortBreak' || after === 'longBreak')) {
+          useToastStore.getState().addToast('完成一个番茄，休息一下', 'success', 4000);
+        } else if (before && before !== 'work' && !after) {
+          useToastStore.getState().addToast('休息结束', 'info', 3000);
+        }
+      });
+      try {
+        set(fromStatus(await getTimerStatus()));
+      } catch (error) {
+        console.error('Failed to load timers:', error);
+      }
+      // tick 丢失时（例如窗口刚恢复）用本地时钟补上，显示不会卡住
+      const interval = window.setInterval(() => {
+        if (get().running.length > 0 || get().pomodoro?.session) set({ now: Date.now() + get().clockOffset });
+      }, 1000);
+      return () => {
+        window.clearInterval(interval);
+        unlisten.then((fn) => fn());
+        unlistenUpdate.then((fn) => fn());
+      };
+    },
+
+    start: (taskId) => run(() => startTimer(taskId)),
+    stop: (taskId) => run(() => stopTimer(taskId)),
+    startPomodoro: (taskId) => run(() => startPomodoro(taskId)),
+    stopPomodoro: () => run(() => stopPomodoro()),
+    resolveIdle: (choice) => run(() => resolveIdle(choice)),
+  };
+});
+
+// 00:42 / 1:05:09
+export function formatElapsed(millis: number): string {
+  const total = Math.max(0, Math.floor(millis / 1000));
+  const h = Math.floor(total / 3600);
+  const m = Math.floor((total % 3600) / 60);
+  const s = total % 60;
+  const pad = (n: number) => String(n).padStart(2, '0');
+  return h > 0 ? `${h}:${pad(m)}:${pad(s)}` : `${pad(m)}:${pad(s)}`;
+}